@@ -0,0 +1,106 @@
+//! NEP-21 token the simulation tests run the amm against.
+//! Only implements what the factory calls, storage is paid by the token account instead of charged to callers.
+
+use near_sdk::{
+    env,
+    near_bindgen,
+    AccountId,
+    json_types::U128,
+    collections::LookupMap,
+    borsh::{
+        self,
+        BorshDeserialize,
+        BorshSerialize
+    }
+};
+
+#[global_allocator]
+static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct MockToken {
+    balances: LookupMap<AccountId, u128>,
+    allowances: LookupMap<(AccountId, AccountId), u128>, // (owner, escrow) => allowance
+    total_supply: u128
+}
+
+impl Default for MockToken {
+    fn default() -> Self {
+        panic!("ERR_NOT_INITIALIZED")
+    }
+}
+
+#[near_bindgen]
+impl MockToken {
+    /**
+     * @notice mints `total_supply` to `owner_id`
+     */
+    #[init]
+    pub fn new(owner_id: AccountId, total_supply: U128) -> Self {
+        assert!(!env::state_exists(), "ERR_ALREADY_INITIALIZED");
+        let mut token = Self {
+            balances: LookupMap::new(b"b".to_vec()),
+            allowances: LookupMap::new(b"a".to_vec()),
+            total_supply: total_supply.into()
+        };
+        token.balances.insert(&owner_id, &total_supply.into());
+        token
+    }
+
+    pub fn inc_allowance(&mut self, escrow_account_id: AccountId, amount: U128) {
+        let key = (env::predecessor_account_id(), escrow_account_id);
+        let allowance = self.allowances.get(&key).unwrap_or(0);
+        self.allowances.insert(&key, &(allowance + u128::from(amount)));
+    }
+
+    pub fn dec_allowance(&mut self, escrow_account_id: AccountId, amount: U128) {
+        let key = (env::predecessor_account_id(), escrow_account_id);
+        let allowance = self.allowances.get(&key).unwrap_or(0);
+        self.allowances.insert(&key, &allowance.saturating_sub(amount.into()));
+    }
+
+    pub fn transfer(&mut self, new_owner_id: AccountId, amount: U128) {
+        self.internal_transfer(&env::predecessor_account_id(), &new_owner_id, amount.into());
+    }
+
+    /**
+     * @notice moves `amount` from `owner_id` to `new_owner_id`, spending the predecessor's allowance unless it's the owner
+     */
+    pub fn transfer_from(&mut self, owner_id: AccountId, new_owner_id: AccountId, amount: U128) {
+        let escrow_account_id = env::predecessor_account_id();
+        let amount = u128::from(amount);
+        if escrow_account_id != owner_id {
+            let key = (owner_id.to_string(), escrow_account_id);
+            let allowance = self.allowances.get(&key).unwrap_or(0);
+            assert!(allowance >= amount, "Not enough allowance");
+            self.allowances.insert(&key, &(allowance - amount));
+        }
+        self.internal_transfer(&owner_id, &new_owner_id, amount);
+    }
+
+    pub fn get_balance(&self, owner_id: AccountId) -> U128 {
+        self.balances.get(&owner_id).unwrap_or(0).into()
+    }
+
+    pub fn get_allowance(&self, owner_id: AccountId, escrow_account_id: AccountId) -> U128 {
+        self.allowances.get(&(owner_id, escrow_account_id)).unwrap_or(0).into()
+    }
+
+    pub fn get_total_supply(&self) -> U128 {
+        self.total_supply.into()
+    }
+}
+
+impl MockToken {
+    fn internal_transfer(&mut self, owner_id: &AccountId, new_owner_id: &AccountId, amount: u128) {
+        assert!(amount > 0, "Can't transfer 0 tokens");
+        assert!(env::is_valid_account_id(new_owner_id.as_bytes()), "New owner's account ID is invalid");
+        let balance = self.balances.get(owner_id).unwrap_or(0);
+        assert!(balance >= amount, "Not enough balance");
+        self.balances.insert(owner_id, &(balance - amount));
+
+        let new_balance = self.balances.get(new_owner_id).unwrap_or(0);
+        self.balances.insert(new_owner_id, &(new_balance + amount));
+    }
+}
@@ -0,0 +1,2 @@
+RUSTFLAGS='-C link-arg=-s' cargo +stable build --manifest-path mock_token/Cargo.toml --target wasm32-unknown-unknown --release
+cp mock_token/target/wasm32-unknown-unknown/release/mock_token.wasm ./res/
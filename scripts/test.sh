@@ -1,2 +1,3 @@
 bash ./scripts/build.sh 
-cargo test -p flux-amm -- --nocapture
\ No newline at end of file
+bash ./scripts/build_mock_token.sh
+cargo test -p flux-amm -- --nocapture
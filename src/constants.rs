@@ -2,6 +2,9 @@ pub const TOKEN_DENOM: u128 = 1_000_000_000_000_000_000;
 
 pub const MIN_FEE: u128= TOKEN_DENOM / 1_000_000;
 pub const MAX_FEE: u128 = TOKEN_DENOM / 10;
+pub const MAX_PROTOCOL_FEE: u128 = TOKEN_DENOM / 2;
+pub const SWAP_FEE_UPDATE_DELAY: u64 = 86_400_000_000_000; // 1 day in nanoseconds
+pub const MIN_WEIGHT_UPDATE_DURATION: u64 = 3_600_000_000_000; // 1 hour in nanoseconds
 
 pub const MIN_BOUND_TOKENS: u64 = 2;
 pub const MAX_BOUND_TOKENS: u64 = 8;
@@ -12,9 +15,14 @@ pub const MIN_BALANCE: u128 = TOKEN_DENOM / 1_000_000_000_000;
 
 pub const MAX_TOTAL_WEIGHT: u128 = TOKEN_DENOM * 50;
 pub const EXIT_FEE: u128 = 0;
+pub const MAX_EXIT_FEE: u128 = TOKEN_DENOM / 10;
 
 pub const INIT_POOL_SUPPLY: u128 = TOKEN_DENOM * 100;
 pub const MAX_IN_RATIO: u128 = TOKEN_DENOM / 2;
+pub const MAX_OUT_RATIO: u128 = (TOKEN_DENOM / 3) + 1;
+
+pub const MAX_OBSERVATIONS: u64 = 64;
+pub const OBSERVATION_PERIOD: u64 = 300_000_000_000; // 5 minutes in nanoseconds
 
 pub const MIN_POW_BASE: u128 = 1;
 pub const MAX_POW_BASE: u128 = (2 * TOKEN_DENOM) - 1;
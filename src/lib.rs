@@ -12,6 +12,8 @@ mod pool;
 mod token;
 mod constants;
 mod math;
+mod oracle;
+pub mod logger;
 
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(test)]
@@ -1,8 +1,245 @@
-// NEW_POOL env log
+use near_sdk::{
+    env,
+    serde_json,
+    json_types::{
+        U128,
+        U64
+    },
+    serde::{
+        Serialize,
+        Deserialize
+    },
+    AccountId
+};
 
-// NEW_OWNER
+/// Version of the event schema, bumped on every breaking change to the structs below
+pub const EVENT_VERSION: &str = "1.0.0";
 
-// LOG_SWAP
-// LOG_JOIN
-// LOG_EXIT
-// LOG_CALL
+/**
+ * @notice every log line is a `VersionedEvent` serialized as json
+ * e.g. `{"version":"1.0.0","type":"swap","params":{...}}`
+ */
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VersionedEvent {
+    pub version: String,
+    #[serde(flatten)]
+    pub event: Event
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde", tag = "type", content = "params", rename_all = "snake_case")]
+pub enum Event {
+    NewPool(NewPoolEvent),
+    Bind(BindEvent),
+    Rebind(BindEvent),
+    Unbind(UnbindEvent),
+    Finalize(FinalizeEvent),
+    Swap(SwapEvent),
+    Join(JoinEvent),
+    Exit(ExitEvent),
+    NewOwner(NewOwnerEvent),
+    TransferFailed(TransferFailedEvent),
+    Gulp(GulpEvent),
+    WeightUpdate(WeightUpdateEvent),
+    PublicSwap(PublicSwapEvent),
+    Pause(PauseEvent),
+    ControllerProposed(ControllerEvent),
+    ControllerChanged(ControllerEvent),
+    LpWhitelistEnabled(LpWhitelistEnabledEvent),
+    LpWhitelistAdd(LpWhitelistEvent),
+    LpWhitelistRemove(LpWhitelistEvent),
+    SwapFeeUpdate(SwapFeeUpdateEvent),
+    DynamicFee(DynamicFeeEvent),
+    ExitFeeUpdate(ExitFeeUpdateEvent),
+    ProtocolFeeSet(ProtocolFeeSetEvent)
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenAmount {
+    pub token: AccountId,
+    pub amount: U128
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NewPoolEvent {
+    pub pool_id: U64,
+    pub controller: AccountId,
+    pub swap_fee: U128
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct BindEvent {
+    pub pool_id: U64,
+    pub token: AccountId,
+    pub denorm: U128,
+    pub balance: U128
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct UnbindEvent {
+    pub pool_id: U64,
+    pub token: AccountId,
+    pub balance: U128
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FinalizeEvent {
+    pub pool_id: U64,
+    pub controller: AccountId,
+    pub pool_tokens_minted: U128
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapEvent {
+    pub pool_id: U64,
+    pub caller: AccountId,
+    pub token_in: AccountId,
+    pub token_out: AccountId,
+    pub token_amount_in: U128,
+    pub token_amount_out: U128,
+    pub spot_price_before: U128,
+    pub spot_price_after: U128,
+    pub swap_fee: U128
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JoinEvent {
+    pub pool_id: U64,
+    pub caller: AccountId,
+    pub tokens_in: Vec<TokenAmount>,
+    pub pool_tokens_minted: U128
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExitEvent {
+    pub pool_id: U64,
+    pub caller: AccountId,
+    pub tokens_out: Vec<TokenAmount>,
+    pub pool_tokens_burned: U128,
+    pub exit_fee: U128
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NewOwnerEvent {
+    pub previous_owner: AccountId,
+    pub new_owner: AccountId
+}
+
+/// Logged when a transfer callback had to roll back a pool's balances
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TransferFailedEvent {
+    pub pool_id: U64,
+    pub token: AccountId,
+    pub account_id: AccountId,
+    pub amount: U128
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct GulpEvent {
+    pub pool_id: U64,
+    pub token: AccountId,
+    pub amount: U128
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WeightUpdateEvent {
+    pub pool_id: U64,
+    pub tokens: Vec<AccountId>,
+    pub start_weights: Vec<U128>,
+    pub end_weights: Vec<U128>,
+    pub start_time: U64,
+    pub end_time: U64
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PublicSwapEvent {
+    pub pool_id: U64,
+    pub public_swap: bool
+}
+
+// `pool_id` is `None` if every pool was paused or unpaused
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PauseEvent {
+    pub pool_id: Option<U64>,
+    pub paused: bool
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ControllerEvent {
+    pub pool_id: U64,
+    pub controller: AccountId,
+    pub new_controller: AccountId
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LpWhitelistEnabledEvent {
+    pub pool_id: U64,
+    pub enabled: bool
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct LpWhitelistEvent {
+    pub pool_id: U64,
+    pub account_ids: Vec<AccountId>
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapFeeUpdateEvent {
+    pub pool_id: U64,
+    pub swap_fee: U128,
+    pub effective_time: U64
+}
+
+// The policy's parameters are `None` if the dynamic fee was turned off
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DynamicFeeEvent {
+    pub pool_id: U64,
+    pub max_fee: Option<U128>,
+    pub sensitivity: Option<U128>,
+    pub decay_period: Option<U64>
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExitFeeUpdateEvent {
+    pub pool_id: U64,
+    pub exit_fee: U128
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProtocolFeeSetEvent {
+    pub protocol_fee: U128
+}
+
+pub fn to_log_string(event: Event) -> String {
+    let versioned_event = VersionedEvent {
+        version: EVENT_VERSION.to_string(),
+        event
+    };
+    serde_json::to_string(&versioned_event).expect("ERR_SERIALIZE_EVENT")
+}
+
+pub fn log(event: Event) {
+    env::log(to_log_string(event).as_bytes());
+}
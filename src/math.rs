@@ -66,6 +66,188 @@ pub fn calc_out_given_in(
     mul_u128(token_balance_out, balance_out_ratio)
 }
 
+/**********************************************************************************************
+// calcInGivenOut                                                                            //
+// aI = tokenAmountIn                                                                        //
+// bO = tokenBalanceOut               /  /     bO      \    (wO / wI)      \                 //
+// bI = tokenBalanceIn          bI * |  | ------------  | ^            - 1  |                //
+// aO = tokenAmountOut    aI =        \  \ ( bO - aO ) /                   /                 //
+// wI = tokenWeightIn           --------------------------------------------                 //
+// wO = tokenWeightOut                          ( 1 - sF )                                   //
+// sF = swapFee                                                                              //
+**********************************************************************************************/
+
+pub fn calc_in_given_out(
+    token_balance_in: u128,
+    token_weight_in: u128,
+    token_balance_out: u128,
+    token_weight_out: u128,
+    token_amount_out: u128,
+    swap_fee: u128
+) -> u128 {
+    let weight_ratio = div_u128(token_weight_out, token_weight_in);
+    let diff = token_balance_out - token_amount_out;
+    let y = div_u128(token_balance_out, diff);
+    let pow_res = pow_u128(y, weight_ratio) - TOKEN_DENOM;
+    let token_amount_in = mul_u128(token_balance_in, pow_res);
+
+    div_u128(token_amount_in, TOKEN_DENOM - swap_fee)
+}
+
+/**********************************************************************************************
+// calcPoolOutGivenSingleIn                                                                  //
+// pAo = poolAmountOut         /                                              \              //
+// tAi = tokenAmountIn        ///      /     //    wI \      \\       \     wI \             //
+// wI = tokenWeightIn        //| tAi *| 1 - || 1 - --  | * sF || + tBi \    --  \            //
+// tW = totalWeight     pAo=||  \      \     \\    tW /      //         | ^ tW   | * pS - pS //
+// tBi = tokenBalanceIn      \\  ------------------------------------- /        /            //
+// pS = poolSupply            \\                    tBi               /        /             //
+// sF = swapFee                \                                              /              //
+**********************************************************************************************/
+
+pub fn calc_pool_out_given_single_in(
+    token_balance_in: u128,
+    token_weight_in: u128,
+    pool_supply: u128,
+    total_weight: u128,
+    token_amount_in: u128,
+    swap_fee: u128
+) -> u128 {
+    // Charge the trading fee for the proportion of tokenAi
+    // which is implicitly traded to the other pool tokens.
+    // That proportion is (1- weightTokenIn)
+    let normalized_weight = div_u128(token_weight_in, total_weight);
+    let zaz = mul_u128(TOKEN_DENOM - normalized_weight, swap_fee);
+    let token_amount_in_after_fee = mul_u128(token_amount_in, TOKEN_DENOM - zaz);
+
+    let new_token_balance_in = token_balance_in + token_amount_in_after_fee;
+    let token_in_ratio = div_u128(new_token_balance_in, token_balance_in);
+
+    let pool_ratio = pow_u128(token_in_ratio, normalized_weight);
+    let new_pool_supply = mul_u128(pool_ratio, pool_supply);
+
+    new_pool_supply - pool_supply
+}
+
+/**********************************************************************************************
+// calcSingleInGivenPoolOut                                                                  //
+// tAi = tokenAmountIn              //(pS + pAo)\     /    1    \\                           //
+// pS = poolSupply                 || ---------  | ^ | --------- || * bI - bI                //
+// pAo = poolAmountOut              \\    pS    /     \(wI / tW)//                           //
+// bI = balanceIn          tAi =  --------------------------------------------               //
+// wI = weightIn                              /      wI  \                                   //
+// tW = totalWeight                          |  1 - ----  |  * sF                            //
+// sF = swapFee                               \      tW  /                                   //
+**********************************************************************************************/
+
+pub fn calc_single_in_given_pool_out(
+    token_balance_in: u128,
+    token_weight_in: u128,
+    pool_supply: u128,
+    total_weight: u128,
+    pool_amount_out: u128,
+    swap_fee: u128
+) -> u128 {
+    let normalized_weight = div_u128(token_weight_in, total_weight);
+    let new_pool_supply = pool_supply + pool_amount_out;
+    let pool_ratio = div_u128(new_pool_supply, pool_supply);
+
+    // uint newBalTi = poolRatio^(1/weightTi) * balTi;
+    let boo = div_u128(TOKEN_DENOM, normalized_weight);
+    let token_in_ratio = pow_u128(pool_ratio, boo);
+    let new_token_balance_in = mul_u128(token_in_ratio, token_balance_in);
+    let token_amount_in_after_fee = new_token_balance_in - token_balance_in;
+
+    // Do reverse order of fees charged in joinswap_ExternAmountIn, this way
+    //     ``` pAo == joinswap_ExternAmountIn(Ti, joinswap_PoolAmountOut(pAo, Ti)) ```
+    let zar = mul_u128(TOKEN_DENOM - normalized_weight, swap_fee);
+
+    div_u128(token_amount_in_after_fee, TOKEN_DENOM - zar)
+}
+
+/**********************************************************************************************
+// calcSingleOutGivenPoolIn                                                                  //
+// tAo = tokenAmountOut            /      /                                             \\   //
+// bO = tokenBalanceOut           /      // pS - (pAi * (1 - eF)) \     /    1    \      \\  //
+// pAi = poolAmountIn            | bO - || ----------------------- | ^ | --------- | * b0 || //
+// ps = poolSupply                \      \\          pS           /     \(wO / tW)/      //  //
+// wI = tokenWeightIn      tAo =   \      \                                             //   //
+// tW = totalWeight                    /     /      wO \       \                             //
+// sF = swapFee                    *  | 1 - |  1 - ---- | * sF  |                            //
+// eF = exitFee                        \     \      tW /       /                             //
+**********************************************************************************************/
+
+pub fn calc_single_out_given_pool_in(
+    token_balance_out: u128,
+    token_weight_out: u128,
+    pool_supply: u128,
+    total_weight: u128,
+    pool_amount_in: u128,
+    swap_fee: u128,
+    exit_fee: u128
+) -> u128 {
+    let normalized_weight = div_u128(token_weight_out, total_weight);
+
+    // charge exit fee on the pool token side
+    // pAiAfterExitFee = pAi*(1-exitFee)
+    let pool_amount_in_after_exit_fee = mul_u128(pool_amount_in, TOKEN_DENOM - exit_fee);
+    let new_pool_supply = pool_supply - pool_amount_in_after_exit_fee;
+    let pool_ratio = div_u128(new_pool_supply, pool_supply);
+
+    // newBalTo = poolRatio^(1/weightTo) * balTo;
+    let token_out_ratio = pow_u128(pool_ratio, div_u128(TOKEN_DENOM, normalized_weight));
+    let new_token_balance_out = mul_u128(token_out_ratio, token_balance_out);
+
+    let token_amount_out_before_swap_fee = token_balance_out - new_token_balance_out;
+
+    // charge swap fee on the output token side
+    //uint tAo = tAoBeforeSwapFee * (1 - (1-weightTo) * swapFee)
+    let zaz = mul_u128(TOKEN_DENOM - normalized_weight, swap_fee);
+
+    mul_u128(token_amount_out_before_swap_fee, TOKEN_DENOM - zaz)
+}
+
+/**********************************************************************************************
+// calcPoolInGivenSingleOut                                                                  //
+// pAi = poolAmountIn               // /               tAo             \\     / wO \     \   //
+// bO = tokenBalanceOut            // | bO - -------------------------- |\   | ---- |     \  //
+// tAo = tokenAmountOut      pS - ||   \     1 - ((1 - (tO / tW)) * sF)/  | ^ \ tW /  * pS | //
+// ps = poolSupply                 \\ -----------------------------------/                /  //
+// wO = tokenWeightOut  pAi =       \\               bO                 /                /   //
+// tW = totalWeight           -------------------------------------------------------------  //
+// sF = swapFee                                        ( 1 - eF )                            //
+// eF = exitFee                                                                              //
+**********************************************************************************************/
+
+pub fn calc_pool_in_given_single_out(
+    token_balance_out: u128,
+    token_weight_out: u128,
+    pool_supply: u128,
+    total_weight: u128,
+    token_amount_out: u128,
+    swap_fee: u128,
+    exit_fee: u128
+) -> u128 {
+    // charge swap fee on the output token side
+    let normalized_weight = div_u128(token_weight_out, total_weight);
+    //uint tAoBeforeSwapFee = tAo / (1 - (1-weightTo) * swapFee) ;
+    let zoo = TOKEN_DENOM - normalized_weight;
+    let zar = mul_u128(zoo, swap_fee);
+    let token_amount_out_before_swap_fee = div_u128(token_amount_out, TOKEN_DENOM - zar);
+
+    let new_token_balance_out = token_balance_out - token_amount_out_before_swap_fee;
+    let token_out_ratio = div_u128(new_token_balance_out, token_balance_out);
+
+    //uint newPoolSupply = (ratioTo ^ weightTo) * poolSupply;
+    let pool_ratio = pow_u128(token_out_ratio, normalized_weight);
+    let new_pool_supply = mul_u128(pool_ratio, pool_supply);
+    let pool_amount_in_after_exit_fee = pool_supply - new_pool_supply;
+
+    // charge exit fee on the pool token side
+    // pAi = pAiAfterExitFee/(1-exitFee)
+    div_u128(pool_amount_in_after_exit_fee, TOKEN_DENOM - exit_fee)
+}
+
 /*** Internal math helper functions ***/
 fn btoi(a: u128) -> u128 {
     a / TOKEN_DENOM
@@ -140,7 +322,7 @@ pub fn pow_u128(
     assert!(base >= MIN_POW_BASE, "ERR_MIN_POW_BASE");
     assert!(base <= MAX_POW_BASE, "ERR_MAX_POW_BASE");
 
-    let whole = floor_u128(base);
+    let whole = floor_u128(exp);
     let remain = exp - whole;
 
     let whole_pow = pow_i_u128(base, btoi(whole));
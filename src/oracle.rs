@@ -0,0 +1,134 @@
+use near_sdk::{
+    borsh::{
+        BorshDeserialize, BorshSerialize
+    },
+    collections::{
+        Vector
+    }
+};
+
+use crate::constants::{
+    MAX_OBSERVATIONS,
+    OBSERVATION_PERIOD
+};
+
+/**
+ * @notice cumulative prices at `timestamp`, every price is summed up weighted by the nanoseconds it was live
+ */
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct Observation {
+    pub timestamp: u64,
+    pub cumulative_prices: Vec<u128>
+}
+
+/**
+ * @notice keeps a ring buffer of the last `MAX_OBSERVATIONS` cumulative prices of a pool, at most one every `OBSERVATION_PERIOD`
+ * @dev cumulative prices are interpolated between observations, which is exact as long as the price didn't change in between.
+ * Throttling observations keeps busy pools from overwriting their history within minutes.
+ * Cumulative prices wrap on overflow, differences are taken with `wrapping_sub` so they stay exact
+ */
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Oracle {
+    last_update: u64,
+    cumulative_prices: Vec<u128>,
+    observations: Vector<Observation>,
+    next_index: u64
+}
+
+impl Oracle {
+    pub fn new(pool_id: u64) -> Self {
+        Self {
+            last_update: 0,
+            cumulative_prices: vec![],
+            observations: Vector::new(format!("observations:{}", pool_id).as_bytes().to_vec()),
+            next_index: 0
+        }
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        !self.observations.is_empty()
+    }
+
+    /**
+     * @notice starts accumulating `num_prices` prices from `timestamp` on
+     */
+    pub fn initialize(&mut self, num_prices: usize, timestamp: u64) {
+        assert!(!self.is_initialized(), "ERR_ORACLE_INITIALIZED");
+        self.last_update = timestamp;
+        self.cumulative_prices = vec![0; num_prices];
+        self.write_observation();
+    }
+
+    /**
+     * @notice adds `prices`, which were live since the last update, to the cumulative prices
+     * @dev only the first update in a block adds time, an observation is only written once `OBSERVATION_PERIOD` passed since the last one
+     */
+    pub fn update(&mut self, prices: &[u128], timestamp: u64) {
+        if timestamp <= self.last_update {
+            return;
+        }
+
+        let elapsed = u128::from(timestamp - self.last_update);
+        for (cumulative_price, price) in self.cumulative_prices.iter_mut().zip(prices.iter()) {
+            *cumulative_price = cumulative_price.wrapping_add(price.wrapping_mul(elapsed));
+        }
+        self.last_update = timestamp;
+
+        if timestamp - self.last_observation_timestamp() >= OBSERVATION_PERIOD {
+            self.write_observation();
+        }
+    }
+
+    /**
+     * @param price the price at `index` that's been live since the last update
+     * @return the cumulative price at `index` at `timestamp`, `None` if `timestamp` is older than every observation
+     */
+    pub fn get_cumulative_price(&self, index: usize, price: u128, timestamp: u64) -> Option<u128> {
+        if timestamp >= self.last_update {
+            let elapsed = u128::from(timestamp - self.last_update);
+            return Some(self.cumulative_prices[index].wrapping_add(price.wrapping_mul(elapsed)));
+        }
+
+        // The newest observation at or before `timestamp` and the oldest one after it
+        let mut before: Option<Observation> = None;
+        let mut after: Option<Observation> = None;
+        for observation in self.observations.iter() {
+            if observation.timestamp <= timestamp {
+                if before.as_ref().map_or(true, |before| observation.timestamp > before.timestamp) {
+                    before = Some(observation);
+                }
+            } else if after.as_ref().map_or(true, |after| observation.timestamp < after.timestamp) {
+                after = Some(observation);
+            }
+        }
+
+        let before = before?;
+        // Past the newest observation the cumulative prices of the last update are the next point
+        let (after_timestamp, after_cumulative_price) = match after {
+            Some(after) => (after.timestamp, after.cumulative_prices[index]),
+            None => (self.last_update, self.cumulative_prices[index])
+        };
+        let price = after_cumulative_price.wrapping_sub(before.cumulative_prices[index]) / u128::from(after_timestamp - before.timestamp);
+
+        Some(before.cumulative_prices[index].wrapping_add(price.wrapping_mul(u128::from(timestamp - before.timestamp))))
+    }
+
+    fn last_observation_timestamp(&self) -> u64 {
+        let last_index = (self.next_index + MAX_OBSERVATIONS - 1) % MAX_OBSERVATIONS;
+        self.observations.get(last_index).map_or(0, |observation| observation.timestamp)
+    }
+
+    fn write_observation(&mut self) {
+        let observation = Observation {
+            timestamp: self.last_update,
+            cumulative_prices: self.cumulative_prices.clone()
+        };
+
+        if self.observations.len() < MAX_OBSERVATIONS {
+            self.observations.push(&observation);
+        } else {
+            self.observations.replace(self.next_index, &observation);
+        }
+        self.next_index = (self.next_index + 1) % MAX_OBSERVATIONS;
+    }
+}
@@ -2,7 +2,13 @@ use std::cmp::Ordering;
 use near_sdk::{
     env,
     json_types::{
-        U128
+        U128,
+        U64
+    },
+    serde::{
+        Serialize,
+        Serializer,
+        Deserialize
     },
     AccountId,
     borsh::{
@@ -10,6 +16,7 @@ use near_sdk::{
     },
     collections::{
         UnorderedMap,
+        UnorderedSet,
         Vector
     }
 };
@@ -22,14 +29,42 @@ use crate::constants::{
     MIN_WEIGHT,
     MAX_WEIGHT,
     EXIT_FEE,
+    MAX_EXIT_FEE,
     MIN_BALANCE,
     MAX_TOTAL_WEIGHT,
     INIT_POOL_SUPPLY,
-    MAX_IN_RATIO
+    MAX_IN_RATIO,
+    MAX_OUT_RATIO,
+    SWAP_FEE_UPDATE_DELAY,
+    MIN_WEIGHT_UPDATE_DURATION
 };
 
 use crate::math;
-use crate::token::FungibleTokenVault;
+use crate::oracle::Oracle;
+use crate::token::{
+    FungibleTokenVault,
+    FungibleTokenMetadata
+};
+use crate::logger::{
+    self,
+    Event,
+    BindEvent,
+    UnbindEvent,
+    FinalizeEvent,
+    SwapEvent,
+    JoinEvent,
+    ExitEvent,
+    WeightUpdateEvent,
+    PublicSwapEvent,
+    PauseEvent,
+    ControllerEvent,
+    LpWhitelistEnabledEvent,
+    LpWhitelistEvent,
+    SwapFeeUpdateEvent,
+    DynamicFeeEvent,
+    ExitFeeUpdateEvent,
+    TokenAmount
+};
 
 #[derive(BorshDeserialize, BorshSerialize, Debug)]
 pub struct Record {
@@ -39,6 +74,248 @@ pub struct Record {
     pub balance: u128, // pool balance of this token
 }
 
+/**
+ * @notice reasons a pool action can't be executed, serialized as the message the action panics with
+ */
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PoolError {
+    NoPool,
+    NotFinalized,
+    NotBound,
+    AmountsLen,
+    MathApprox,
+    LimitIn,
+    LimitOut,
+    MaxInRatio,
+    MaxOutRatio,
+    BadLimitPrice,
+    LimitPrice,
+    SwapNotPublic,
+    Paused,
+    ZeroAmount,
+    Locked
+}
+
+impl PoolError {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PoolError::NoPool => "ERR_NO_POOL",
+            PoolError::NotFinalized => "ERR_NOT_FINALIZED",
+            PoolError::NotBound => "ERR_NOT_BOUND",
+            PoolError::AmountsLen => "ERR_AMOUNTS_LEN",
+            PoolError::MathApprox => "ERR_MATH_APPROX",
+            PoolError::LimitIn => "ERR_LIMIT_IN",
+            PoolError::LimitOut => "ERR_LIMIT_OUT",
+            PoolError::MaxInRatio => "ERR_MAX_IN_RATIO",
+            PoolError::MaxOutRatio => "ERR_MAX_OUT_RATIO",
+            PoolError::BadLimitPrice => "ERR_BAD_LIMIT_PRICE",
+            PoolError::LimitPrice => "ERR_LIMIT_PRICE",
+            PoolError::SwapNotPublic => "ERR_SWAP_NOT_PUBLIC",
+            PoolError::Paused => "ERR_PAUSED",
+            PoolError::ZeroAmount => "ERR_ZERO_AMOUNT",
+            PoolError::Locked => "ERR_REENTRY"
+        }
+    }
+}
+
+// Serialized through `as_str` so the JSON of a preview can't drift from the panic message
+impl Serialize for PoolError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+pub type PoolResult<T> = Result<T, PoolError>;
+
+fn ensure(condition: bool, error: PoolError) -> PoolResult<()> {
+    if condition {
+        Ok(())
+    } else {
+        Err(error)
+    }
+}
+
+// Mutating paths panic with the same message the preview returns
+fn expect_ok<T>(result: PoolResult<T>) -> T {
+    result.unwrap_or_else(|error| panic!("{}", error.as_str()))
+}
+
+/**
+ * @notice the outcome of a swap, returned by the preview views and applied by the swap methods
+ */
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapPreview {
+    pub token_amount_in: U128,
+    pub token_amount_out: U128,
+    pub spot_price_before: U128,
+    pub spot_price_after: U128
+}
+
+/**
+ * @notice snapshot of a pool's state, weights are the ones at the current block timestamp
+ */
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PoolInfo {
+    pub id: U64,
+    pub controller: AccountId,
+    pub finalized: bool,
+    pub swap_fee: U128,
+    pub total_weight: U128,
+    pub total_supply: U128,
+    pub tokens: Vec<PoolTokenInfo>
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PoolTokenInfo {
+    pub token: AccountId,
+    pub denorm: U128,
+    pub normalized_weight: U128,
+    pub balance: U128
+}
+
+/**
+ * @notice a swap fee change that's scheduled by the controller of a finalized pool
+ */
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingSwapFee {
+    pub swap_fee: U128,
+    pub effective_time: U64
+}
+
+/**
+ * @notice a dynamic fee policy and its current surcharge on top of the base fee
+ */
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DynamicFeeInfo {
+    pub base_fee: U128,
+    pub max_fee: U128,
+    pub sensitivity: U128,
+    pub decay_period: U64,
+    pub surcharge: U128
+}
+
+/**
+ * @notice raises the swap fee after price moves, the surcharge decays linearly back to the base fee over `decay_period`
+ * @dev a move only raises the fee from the next block on, every swap in a block is charged the same fee
+ */
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct DynamicFee {
+    pub max_fee: u128,
+    pub sensitivity: u128,
+    pub decay_period: u64,
+    surcharge: u128, // surcharge at `last_update`
+    block_surcharge: u128, // added by the swaps in the block of `last_update`
+    last_update: u64,
+    uncommitted: Option<(u128, u128, u64)> // surcharge, block surcharge and last update before the swap in flight
+}
+
+impl DynamicFee {
+    fn new(max_fee: u128, sensitivity: u128, decay_period: u64, timestamp: u64) -> Self {
+        Self {
+            max_fee,
+            sensitivity,
+            decay_period,
+            surcharge: 0,
+            block_surcharge: 0,
+            last_update: timestamp,
+            uncommitted: None
+        }
+    }
+
+    fn get_surcharge(&self, timestamp: u64) -> u128 {
+        if timestamp <= self.last_update {
+            return self.surcharge;
+        }
+
+        let elapsed = u128::from(timestamp - self.last_update);
+        let decay_period = u128::from(self.decay_period);
+        if elapsed >= decay_period {
+            return 0;
+        }
+
+        (self.surcharge + self.block_surcharge) * (decay_period - elapsed) / decay_period
+    }
+
+    fn get_swap_fee(&self, base_fee: u128, timestamp: u64) -> u128 {
+        std::cmp::min(base_fee + self.get_surcharge(timestamp), std::cmp::max(self.max_fee, base_fee))
+    }
+
+    // The stored surcharge never exceeds `max_fee - base_fee` so it starts decaying from the fee that's actually charged
+    fn record_price_move(&mut self, base_fee: u128, spot_price_before: u128, spot_price_after: u128, timestamp: u64) {
+        if self.uncommitted.is_none() {
+            self.uncommitted = Some((self.surcharge, self.block_surcharge, self.last_update));
+        }
+
+        let max_surcharge = self.max_fee.saturating_sub(base_fee);
+        if timestamp > self.last_update {
+            self.surcharge = std::cmp::min(self.get_surcharge(timestamp), max_surcharge);
+            self.block_surcharge = 0;
+            self.last_update = timestamp;
+        }
+
+        let price_move = if spot_price_after > spot_price_before {
+            spot_price_after - spot_price_before
+        } else {
+            spot_price_before - spot_price_after
+        };
+        let surcharge = math::mul_u128(math::div_u128(price_move, spot_price_before), self.sensitivity);
+        self.block_surcharge = std::cmp::min(self.block_surcharge + surcharge, max_surcharge.saturating_sub(self.surcharge));
+    }
+
+    // Called when the pool is locked for a new operation, the previous one can't be rolled back anymore
+    fn commit(&mut self) {
+        self.uncommitted = None;
+    }
+
+    fn rollback(&mut self) {
+        if let Some((surcharge, block_surcharge, last_update)) = self.uncommitted.take() {
+            self.surcharge = surcharge;
+            self.block_surcharge = block_surcharge;
+            self.last_update = last_update;
+        }
+    }
+}
+
+/**
+ * @notice a linear change of token weights between `start_time` and `end_time`
+ * @dev weights are indexed like `tokens`, timestamps are block timestamps in nanoseconds
+ */
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
+pub struct WeightUpdate {
+    pub tokens: Vec<AccountId>,
+    pub start_weights: Vec<u128>,
+    pub end_weights: Vec<u128>,
+    pub start_time: u64,
+    pub end_time: u64
+}
+
+impl WeightUpdate {
+    fn get_weight(&self, index: usize, timestamp: u64) -> u128 {
+        let start_weight = self.start_weights[index];
+        let end_weight = self.end_weights[index];
+
+        if timestamp <= self.start_time {
+            return start_weight;
+        } else if timestamp >= self.end_time {
+            return end_weight;
+        }
+
+        let elapsed = u128::from(timestamp - self.start_time);
+        let duration = u128::from(self.end_time - self.start_time);
+
+        if end_weight >= start_weight {
+            start_weight + (end_weight - start_weight) * elapsed / duration
+        } else {
+            start_weight - (start_weight - end_weight) * elapsed / duration
+        }
+    }
+}
+
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct Pool {
     id: u64,
@@ -48,7 +325,18 @@ pub struct Pool {
     controller: AccountId,
     token: FungibleTokenVault,
     pub records: UnorderedMap<AccountId, Record>,
-    pub tokens: Vector<AccountId>
+    pub tokens: Vector<AccountId>,
+    weight_update: Option<WeightUpdate>,
+    oracle: Oracle,
+    locked: bool, // set while a swap's transfers are in flight, released by its callback
+    public_swap: bool, // can anyone swap against this pool
+    paused: bool, // set by the factory owner, blocks swaps and joins but not exits
+    pending_controller: Option<AccountId>, // proposed by the controller, becomes the controller once it accepts
+    lp_whitelist_enabled: bool, // if set only the controller and whitelisted accounts can join
+    lp_whitelist: UnorderedSet<AccountId>,
+    pending_swap_fee: Option<(u128, u64)>, // swap fee that replaces `swap_fee` at the block timestamp it's paired with
+    dynamic_fee: Option<DynamicFee>,
+    exit_fee: u128 // fraction of the pool tokens of every exit that goes to the factory owner
 }
 
 impl Pool {
@@ -61,8 +349,8 @@ impl Pool {
         id: u64, 
         swap_fee: u128
     ) -> Self {
+        assert!(swap_fee >= MIN_FEE, "ERR_MIN_FEE");
         assert!(swap_fee <= MAX_FEE, "ERR_MAX_FEE");
-        // assert!(swap_fee >= MIN_FEE, "ERR_MIN_FEE"); // TODO: Turn on
 
         Self {
             id,
@@ -73,6 +361,17 @@ impl Pool {
             token: FungibleTokenVault::new(id),
             records: UnorderedMap::new(format!("records:{}", id).as_bytes().to_vec()),
             tokens: Vector::new(format!("tokens:{}", id).as_bytes().to_vec()),
+            weight_update: None,
+            oracle: Oracle::new(id),
+            locked: false,
+            public_swap: false,
+            paused: false,
+            pending_controller: None,
+            lp_whitelist_enabled: false,
+            lp_whitelist: UnorderedSet::new(format!("lp_whitelist:{}", id).as_bytes().to_vec()),
+            pending_swap_fee: None,
+            dynamic_fee: None,
+            exit_fee: EXIT_FEE
         }
     }
 
@@ -87,10 +386,166 @@ impl Pool {
         }
     }
 
+    pub fn is_public_swap(&self) -> bool {
+        self.public_swap
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /**
+     * @notice lets anyone swap against the pool before it's finalized, finalizing always enables swaps
+     */
+    pub fn set_public_swap(&mut self, sender: &AccountId, public_swap: bool) {
+        assert_eq!(sender, &self.controller, "ERR_NO_CONTROLLER");
+        assert!(!self.finalized, "ERR_IS_FINALIZED");
+        self.public_swap = public_swap;
+
+        logger::log(Event::PublicSwap(PublicSwapEvent {
+            pool_id: self.id.into(),
+            public_swap
+        }));
+    }
+
+    /**
+     * @notice blocks swaps and joins while `paused`, exits keep working so LPs can always withdraw
+     */
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+
+        logger::log(Event::Pause(PauseEvent {
+            pool_id: Some(self.id.into()),
+            paused
+        }));
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /**
+     * @notice blocks every method that changes the pool's balances, weights or supply until the pool is unlocked again
+     * @dev taken by swaps, joins and gulps before they change anything and held until their callback resolves,
+     * rollbacks in callbacks don't check it
+     */
+    pub fn lock(&mut self) {
+        self.assert_unlocked();
+        self.locked = true;
+        if let Some(dynamic_fee) = self.dynamic_fee.as_mut() {
+            dynamic_fee.commit();
+        }
+    }
+
+    pub fn unlock(&mut self) {
+        self.locked = false;
+    }
+
+    fn check_unlocked(&self) -> PoolResult<()> {
+        ensure(!self.locked, PoolError::Locked)
+    }
+
+    fn assert_unlocked(&self) {
+        expect_ok(self.check_unlocked());
+    }
+
     pub fn get_controller(&self) -> AccountId {
         self.controller.to_string()
     }
 
+    pub fn get_pending_controller(&self) -> Option<AccountId> {
+        self.pending_controller.clone()
+    }
+
+    /**
+     * @notice proposes `new_controller`, the controller only changes once `new_controller` accepts
+     * @dev a new proposal replaces the pending one, neither can happen while the pool is locked
+     *      since callbacks act as the controller that started the operation
+     */
+    pub fn set_controller(&mut self, sender: &AccountId, new_controller: &AccountId) {
+        self.assert_unlocked();
+        assert_eq!(sender, &self.controller, "ERR_NO_CONTROLLER");
+        assert!(env::is_valid_account_id(new_controller.as_bytes()), "ERR_INVALID_ACCOUNT_ID");
+        self.pending_controller = Some(new_controller.to_string());
+
+        logger::log(Event::ControllerProposed(ControllerEvent {
+            pool_id: self.id.into(),
+            controller: self.controller.to_string(),
+            new_controller: new_controller.to_string()
+        }));
+    }
+
+    pub fn accept_controller(&mut self, sender: &AccountId) {
+        self.assert_unlocked();
+        assert_eq!(Some(sender), self.pending_controller.as_ref(), "ERR_NOT_PENDING_CONTROLLER");
+        let controller = std::mem::replace(&mut self.controller, sender.to_string());
+        self.pending_controller = None;
+
+        logger::log(Event::ControllerChanged(ControllerEvent {
+            pool_id: self.id.into(),
+            controller,
+            new_controller: sender.to_string()
+        }));
+    }
+
+    pub fn is_lp_whitelist_enabled(&self) -> bool {
+        self.lp_whitelist_enabled
+    }
+
+    /**
+     * @return true if `account_id` can join the pool
+     */
+    pub fn is_whitelisted_lp(&self, account_id: &AccountId) -> bool {
+        !self.lp_whitelist_enabled || account_id == &self.controller || self.lp_whitelist.contains(account_id)
+    }
+
+    pub fn get_lp_whitelist(&self, from_index: u64, limit: u64) -> Vec<AccountId> {
+        self.lp_whitelist
+            .iter()
+            .skip(from_index as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    /**
+     * @notice makes the pool private, only the controller and whitelisted accounts can join while `enabled`
+     * @dev LPs that aren't whitelisted can still exit
+     */
+    pub fn set_lp_whitelist_enabled(&mut self, sender: &AccountId, enabled: bool) {
+        assert_eq!(sender, &self.controller, "ERR_NO_CONTROLLER");
+        self.lp_whitelist_enabled = enabled;
+
+        logger::log(Event::LpWhitelistEnabled(LpWhitelistEnabledEvent {
+            pool_id: self.id.into(),
+            enabled
+        }));
+    }
+
+    pub fn add_to_lp_whitelist(&mut self, sender: &AccountId, account_ids: Vec<AccountId>) {
+        assert_eq!(sender, &self.controller, "ERR_NO_CONTROLLER");
+        for account_id in &account_ids {
+            assert!(env::is_valid_account_id(account_id.as_bytes()), "ERR_INVALID_ACCOUNT_ID");
+            self.lp_whitelist.insert(account_id);
+        }
+
+        logger::log(Event::LpWhitelistAdd(LpWhitelistEvent {
+            pool_id: self.id.into(),
+            account_ids
+        }));
+    }
+
+    pub fn remove_from_lp_whitelist(&mut self, sender: &AccountId, account_ids: Vec<AccountId>) {
+        assert_eq!(sender, &self.controller, "ERR_NO_CONTROLLER");
+        for account_id in &account_ids {
+            self.lp_whitelist.remove(account_id);
+        }
+
+        logger::log(Event::LpWhitelistRemove(LpWhitelistEvent {
+            pool_id: self.id.into(),
+            account_ids
+        }));
+    }
+
     pub fn get_num_tokens(&self) -> u64 {
         self.tokens.len()
     }
@@ -119,17 +574,367 @@ impl Pool {
         self.token.total_supply()
     }
 
+    /**
+     * @return the swap fee at the current block timestamp including the dynamic fee's surcharge
+     */
     pub fn get_swap_fee(&self) -> u128 {
-        self.swap_fee
+        let base_fee = self.get_base_swap_fee();
+        match &self.dynamic_fee {
+            Some(dynamic_fee) => dynamic_fee.get_swap_fee(base_fee, env::block_timestamp()),
+            None => base_fee
+        }
+    }
+
+    /**
+     * @return the swap fee set by the controller, a scheduled fee counts once it's effective
+     */
+    pub fn get_base_swap_fee(&self) -> u128 {
+        match self.pending_swap_fee {
+            Some((swap_fee, effective_time)) if effective_time <= env::block_timestamp() => swap_fee,
+            _ => self.swap_fee
+        }
+    }
+
+    pub fn get_dynamic_fee(&self) -> Option<DynamicFeeInfo> {
+        self.dynamic_fee.as_ref().map(|dynamic_fee| DynamicFeeInfo {
+            base_fee: self.get_base_swap_fee().into(),
+            max_fee: dynamic_fee.max_fee.into(),
+            sensitivity: dynamic_fee.sensitivity.into(),
+            decay_period: dynamic_fee.decay_period.into(),
+            surcharge: dynamic_fee.get_surcharge(env::block_timestamp()).into()
+        })
+    }
+
+    /**
+     * @notice raises the swap fee by `sensitivity` times the relative price move of every swap, up to `max_fee`
+     * @param policy `max_fee`, `sensitivity` and the nanoseconds it takes a surcharge to decay back to the base fee
+     * @dev replaces the current policy and its surcharge, `None` turns the dynamic fee off
+     */
+    pub fn set_dynamic_fee(
+        &mut self,
+        sender: &AccountId,
+        policy: Option<(u128, u128, u64)>
+    ) {
+        assert_eq!(sender, &self.controller, "ERR_NO_CONTROLLER");
+
+        let base_fee = self.get_base_swap_fee();
+        self.dynamic_fee = policy.map(|(max_fee, sensitivity, decay_period)| {
+            assert!(max_fee <= MAX_FEE, "ERR_MAX_FEE");
+            assert!(max_fee >= base_fee, "ERR_MIN_FEE");
+            assert!(decay_period > 0, "ERR_INVALID_DECAY_PERIOD");
+            DynamicFee::new(max_fee, sensitivity, decay_period, env::block_timestamp())
+        });
+
+        logger::log(Event::DynamicFee(DynamicFeeEvent {
+            pool_id: self.id.into(),
+            max_fee: self.dynamic_fee.as_ref().map(|dynamic_fee| dynamic_fee.max_fee.into()),
+            sensitivity: self.dynamic_fee.as_ref().map(|dynamic_fee| dynamic_fee.sensitivity.into()),
+            decay_period: self.dynamic_fee.as_ref().map(|dynamic_fee| dynamic_fee.decay_period.into())
+        }));
+    }
+
+    pub fn get_pending_swap_fee(&self) -> Option<PendingSwapFee> {
+        self.pending_swap_fee
+            .filter(|(_, effective_time)| *effective_time > env::block_timestamp())
+            .map(|(swap_fee, effective_time)| PendingSwapFee {
+                swap_fee: swap_fee.into(),
+                effective_time: effective_time.into()
+            })
+    }
+
+    /**
+     * @notice changes the swap fee right away before finalization, afterwards it takes effect `SWAP_FEE_UPDATE_DELAY` from now
+     * @dev a new fee replaces the one that's pending, restarting the delay
+     */
+    pub fn set_swap_fee(&mut self, sender: &AccountId, swap_fee: u128) {
+        assert_eq!(sender, &self.controller, "ERR_NO_CONTROLLER");
+        assert!(swap_fee >= MIN_FEE, "ERR_MIN_FEE");
+        assert!(swap_fee <= MAX_FEE, "ERR_MAX_FEE");
+
+        self.swap_fee = self.get_base_swap_fee();
+        let effective_time = if self.finalized {
+            let effective_time = env::block_timestamp() + SWAP_FEE_UPDATE_DELAY;
+            self.pending_swap_fee = Some((swap_fee, effective_time));
+            effective_time
+        } else {
+            self.swap_fee = swap_fee;
+            self.pending_swap_fee = None;
+            env::block_timestamp()
+        };
+
+        logger::log(Event::SwapFeeUpdate(SwapFeeUpdateEvent {
+            pool_id: self.id.into(),
+            swap_fee: swap_fee.into(),
+            effective_time: effective_time.into()
+        }));
+    }
+
+    pub fn get_exit_fee(&self) -> u128 {
+        self.exit_fee
+    }
+
+    /**
+     * @notice sets the fraction of the pool tokens of every exit that's paid to the factory owner
+     * @dev fixed once the pool is finalized so LPs know the fee they'll pay when joining
+     */
+    pub fn set_exit_fee(&mut self, sender: &AccountId, exit_fee: u128) {
+        assert_eq!(sender, &self.controller, "ERR_NO_CONTROLLER");
+        assert!(!self.finalized, "ERR_IS_FINALIZED");
+        assert!(exit_fee <= MAX_EXIT_FEE, "ERR_MAX_EXIT_FEE");
+        self.exit_fee = exit_fee;
+
+        logger::log(Event::ExitFeeUpdate(ExitFeeUpdateEvent {
+            pool_id: self.id.into(),
+            exit_fee: exit_fee.into()
+        }));
+    }
+
+    pub fn get_info(&self) -> PoolInfo {
+        let total_weight = self.get_total_denormalized_weight();
+        let tokens = self.tokens
+            .iter()
+            .map(|token| {
+                let record = self.records.get(&token).expect("ERR_NO_RECORD");
+                let denorm = self.get_denormalized_weight(&token);
+                PoolTokenInfo {
+                    token,
+                    denorm: denorm.into(),
+                    normalized_weight: math::div_u128(denorm, total_weight).into(),
+                    balance: record.balance.into()
+                }
+            })
+            .collect();
+
+        PoolInfo {
+            id: self.id.into(),
+            controller: self.controller.to_string(),
+            finalized: self.finalized,
+            swap_fee: self.get_swap_fee().into(),
+            total_weight: total_weight.into(),
+            total_supply: self.token.total_supply().into(),
+            tokens
+        }
+    }
+
+    /**
+     * @return the weight of `token_account_id` at the current block timestamp
+     */
+    pub fn get_denormalized_weight(&self, token_account_id: &AccountId) -> u128 {
+        assert!(self.is_bound(token_account_id), "ERR_NOT_BOUND");
+        let record = self.records.get(token_account_id).expect("ERR_NO_RECORD");
+
+        match &self.weight_update {
+            Some(update) => match update.tokens.iter().position(|token| token == token_account_id) {
+                Some(index) => update.get_weight(index, env::block_timestamp()),
+                None => record.denorm
+            },
+            None => record.denorm
+        }
+    }
+
+    /**
+     * @return the sum of all weights at the current block timestamp
+     */
+    pub fn get_total_denormalized_weight(&self) -> u128 {
+        match &self.weight_update {
+            Some(update) => update.tokens.iter().enumerate().fold(self.total_weight, |total, (index, token)| {
+                let record = self.records.get(token).expect("ERR_NO_RECORD");
+                total - record.denorm + update.get_weight(index, env::block_timestamp())
+            }),
+            None => self.total_weight
+        }
+    }
+
+    /**
+     * @notice schedules a linear weight change for `tokens` from their current weights to `end_weights`
+     * @dev replaces any running update from the weights it reached, the weights hold until `start_time`
+     *      so the schedule can't jump them, both ends are bounded so the weights are bounded in between
+     */
+    pub fn update_weights_gradually(
+        &mut self,
+        sender: &AccountId,
+        tokens: Vec<AccountId>,
+        end_weights: Vec<u128>,
+        start_time: u64,
+        end_time: u64
+    ) {
+        self.assert_unlocked();
+        assert_eq!(sender, &self.controller, "ERR_NO_CONTROLLER");
+        assert!(self.finalized, "ERR_NOT_FINALIZED");
+        assert_eq!(tokens.len(), end_weights.len(), "ERR_WEIGHTS_LEN");
+        assert!(start_time >= env::block_timestamp(), "ERR_INVALID_SCHEDULE");
+        assert!(end_time.saturating_sub(start_time) >= MIN_WEIGHT_UPDATE_DURATION, "ERR_MIN_DURATION");
+
+        self.update_oracle();
+        self.poke_weights();
+        self.weight_update = None;
+
+        let mut start_weights = Vec::with_capacity(tokens.len());
+        let mut end_total_weight = self.total_weight;
+        for (i, token) in tokens.iter().enumerate() {
+            assert!(self.is_bound(token), "ERR_NOT_BOUND");
+            assert!(!tokens[..i].contains(token), "ERR_DUPLICATE_TOKEN");
+            assert!(end_weights[i] >= MIN_WEIGHT, "ERR_MIN_WEIGHT");
+            assert!(end_weights[i] <= MAX_WEIGHT, "ERR_MAX_WEIGHT");
+
+            let denorm = self.records.get(token).expect("ERR_NO_RECORD").denorm;
+            start_weights.push(denorm);
+            end_total_weight = end_total_weight - denorm + end_weights[i];
+        }
+        assert!(end_total_weight <= MAX_TOTAL_WEIGHT, "ERR_MAX_TOTAL_WEIGHT");
+
+        logger::log(Event::WeightUpdate(WeightUpdateEvent {
+            pool_id: self.id.into(),
+            tokens: tokens.clone(),
+            start_weights: start_weights.iter().map(|weight| U128(*weight)).collect(),
+            end_weights: end_weights.iter().map(|weight| U128(*weight)).collect(),
+            start_time: start_time.into(),
+            end_time: end_time.into()
+        }));
+
+        self.weight_update = Some(WeightUpdate {
+            tokens,
+            start_weights,
+            end_weights,
+            start_time,
+            end_time
+        });
+    }
+
+    /**
+     * @return the time-weighted average spot price, without fees, of `token_in` in `token_out` over the last `window` nanoseconds
+     */
+    pub fn get_twap(
+        &self,
+        token_in: &AccountId,
+        token_out: &AccountId,
+        window: u64
+    ) -> u128 {
+        assert!(self.is_bound(token_in), "ERR_NOT_BOUND");
+        assert!(self.is_bound(token_out), "ERR_NOT_BOUND");
+        assert_ne!(token_in, token_out, "ERR_SAME_TOKEN");
+        assert!(self.oracle.is_initialized(), "ERR_NOT_FINALIZED");
+        assert!(window > 0, "ERR_INVALID_WINDOW");
+
+        let index = self.get_price_index(token_in, token_out);
+        let price = self.get_oracle_prices()[index];
+        let now = env::block_timestamp();
+        assert!(window <= now, "ERR_INSUFFICIENT_HISTORY");
+
+        let cumulative_price_now = self.oracle.get_cumulative_price(index, price, now).expect("ERR_INSUFFICIENT_HISTORY");
+        let cumulative_price_then = self.oracle.get_cumulative_price(index, price, now - window).expect("ERR_INSUFFICIENT_HISTORY");
+
+        cumulative_price_now.wrapping_sub(cumulative_price_then) / u128::from(window)
+    }
+
+    // Prices are kept for every ordered pair of tokens, skipping pairs of a token with itself
+    fn get_price_index(&self, token_in: &AccountId, token_out: &AccountId) -> usize {
+        let num_tokens = self.get_num_tokens() as usize;
+        let index_in = self.records.get(token_in).expect("ERR_NO_RECORD").index as usize;
+        let index_out = self.records.get(token_out).expect("ERR_NO_RECORD").index as usize;
+
+        index_in * (num_tokens - 1) + if index_out < index_in { index_out } else { index_out - 1 }
+    }
+
+    /**
+     * @return the spot price without fees of every ordered pair of tokens, at the weights as of the last poke
+     */
+    fn get_oracle_prices(&self) -> Vec<u128> {
+        let records: Vec<Record> = self.tokens
+            .iter()
+            .map(|token| self.records.get(&token).expect("ERR_NO_RECORD"))
+            .collect();
+
+        let mut prices = Vec::with_capacity(records.len() * (records.len() - 1));
+        for (i, record_in) in records.iter().enumerate() {
+            for (j, record_out) in records.iter().enumerate() {
+                if i != j {
+                    prices.push(math::calc_spot_price(record_in.balance, record_in.denorm, record_out.balance, record_out.denorm, 0));
+                }
+            }
+        }
+        prices
+    }
+
+    /**
+     * @notice accumulates the prices that were live since the last update, called before every balance or weight change
+     */
+    fn update_oracle(&mut self) {
+        if !self.oracle.is_initialized() {
+            return;
+        }
+        let prices = self.get_oracle_prices();
+        self.oracle.update(&prices, env::block_timestamp());
+    }
+
+    /**
+     * @notice writes the scheduled weights at the current block timestamp to the records
+     * @dev called before every operation that prices against the weights, the update is dropped once it's complete
+     */
+    fn poke_weights(&mut self) {
+        let update = match self.weight_update.take() {
+            Some(update) => update,
+            None => return
+        };
+        let timestamp = env::block_timestamp();
+
+        for (index, token) in update.tokens.iter().enumerate() {
+            let mut record = self.records.get(token).expect("ERR_NO_RECORD");
+            let weight = update.get_weight(index, timestamp);
+            self.total_weight = self.total_weight - record.denorm + weight;
+            record.denorm = weight;
+            self.records.insert(token, &record);
+        }
+
+        if timestamp < update.end_time {
+            self.weight_update = Some(update);
+        }
+    }
+
+    /**
+     * @notice derives the pool token's metadata from the bound tokens
+     * e.g. a pool of `dai.near` and `mkr.near` is called "Flux AMM DAI/MKR #1" with symbol "DAI-MKR"
+     */
+    pub fn get_pool_token_metadata(&self) -> FungibleTokenMetadata {
+        let symbols: Vec<String> = self.tokens
+            .iter()
+            .map(|token| token.split('.').next().unwrap_or(&token).to_uppercase())
+            .collect();
+
+        FungibleTokenMetadata {
+            spec: "ft-1.0.0".to_string(),
+            name: format!("Flux AMM {} #{}", symbols.join("/"), self.id),
+            symbol: symbols.join("-"),
+            decimals: 18,
+        }
+    }
+
+    pub fn transfer_pool_tokens(
+        &mut self,
+        sender: &AccountId,
+        receiver_id: &AccountId,
+        amount: u128
+    ) {
+        self.token.transfer(sender, receiver_id, amount);
     }
 
     pub fn finalize(&mut self, sender: &AccountId) {
+        self.assert_unlocked();
         assert!(!self.finalized, "ERR_IS_FINALIZED");
         assert!(self.get_num_tokens() >= MIN_BOUND_TOKENS, "ERR_MIN_TOKENS");
         assert_eq!(sender, &self.controller, "ERR_NO_CONTROLLER");
 
         self.finalized = true;
+        self.public_swap = true;
         self.token.mint(INIT_POOL_SUPPLY, sender);
+        let num_tokens = self.get_num_tokens() as usize;
+        self.oracle.initialize(num_tokens * (num_tokens - 1), env::block_timestamp());
+
+        logger::log(Event::Finalize(FinalizeEvent {
+            pool_id: self.id.into(),
+            controller: sender.to_string(),
+            pool_tokens_minted: INIT_POOL_SUPPLY.into()
+        }));
     }
 
     pub fn bind(&mut self, 
@@ -138,6 +943,7 @@ impl Pool {
         denorm: u128, 
         balance: u128
     ) {
+        self.assert_unlocked();
         assert_eq!(sender, &self.controller, "ERR_NO_CONTROLLER");
         assert!(env::is_valid_account_id(token_account_id.as_bytes()), "ERR_INVALID_ACCOUNT_ID");
         assert!(!self.is_bound(&token_account_id), "ERR_is_BOUND");
@@ -153,7 +959,14 @@ impl Pool {
         
         self.records.insert(token_account_id, &new_record);
         self.tokens.push(token_account_id);
-        self.rebind(sender, token_account_id, denorm, balance);
+        self.internal_rebind(sender, token_account_id, denorm, balance);
+
+        logger::log(Event::Bind(BindEvent {
+            pool_id: self.id.into(),
+            token: token_account_id.to_string(),
+            denorm: denorm.into(),
+            balance: balance.into()
+        }));
     }
     
     pub fn rebind(
@@ -163,10 +976,28 @@ impl Pool {
         denorm: u128, 
         balance: u128
     ) {
-        assert_eq!(sender, &self.controller, "ERR_NO_CONTROLLER");
-        assert!(env::is_valid_account_id(token_account_id.as_bytes()), "ERR_INVALID_ACCOUNT_ID");
-        assert!(self.is_bound(token_account_id), "ERR_NOT_BOUND");
-        assert!(!self.finalized, "ERR_IS_FINALIZED");
+        self.assert_unlocked();
+        self.internal_rebind(sender, token_account_id, denorm, balance);
+
+        logger::log(Event::Rebind(BindEvent {
+            pool_id: self.id.into(),
+            token: token_account_id.to_string(),
+            denorm: denorm.into(),
+            balance: balance.into()
+        }));
+    }
+
+    fn internal_rebind(
+        &mut self, 
+        sender: &AccountId, 
+        token_account_id: &AccountId, 
+        denorm: u128, 
+        balance: u128
+    ) {
+        assert_eq!(sender, &self.controller, "ERR_NO_CONTROLLER");
+        assert!(env::is_valid_account_id(token_account_id.as_bytes()), "ERR_INVALID_ACCOUNT_ID");
+        assert!(self.is_bound(token_account_id), "ERR_NOT_BOUND");
+        assert!(!self.finalized, "ERR_IS_FINALIZED");
         
         assert!(denorm >= MIN_WEIGHT, "ERR_MIN_WEIGHT");
         assert!(denorm <= MAX_WEIGHT, "ERR_MAX_WEIGHT");
@@ -187,53 +1018,102 @@ impl Pool {
         };
 
         record.denorm = denorm;
-
-        // let old_balance = record.balance;
         record.balance = balance;
 
         self.records.insert(token_account_id, &record);
-        
-        // match balance.cmp(&old_balance) {
-        //     Ordering::Greater => {
-        //         // Transfer from user to this contract 
-        //         // token(token_account_id).transfer_from(env::predecessor_account(), old_balance - balance)
-        //     },
-        //     Ordering::Less => {
-        //         // let token_balance_withdraw = old_balance - balance;
-        //         // let token_exit_fee = token_balance_withdraw * EXIT_FEE; // TODO: do we need this?
-
-        //         // Transfer from contract to this user
-        //         // token(token_account_id).transfer(env::predecessor_account(), token_balance_withdraw - token_exit_fee)
-        //     },
-        //     Ordering::Equal => ()
-        // }
-        
     }
 
+    /**
+     * @notice removes `token_account_id` from the pool
+     * @return the balance of the unbound token that has to be returned to the controller
+     */
     pub fn unbind(
         &mut self, 
         sender: &AccountId, 
         token_account_id: &AccountId
-    ) {
+    ) -> u128 {
+        self.assert_unlocked();
         assert_eq!(sender, &self.controller, "ERR_NO_CONTROLLER");
         assert!(self.is_bound(token_account_id), "ERR_NOT_BOUND");
         assert!(!self.finalized, "ERR_IS_FINALIZED");
 
         let record = self.records.get(token_account_id).expect("ERR_NO_RECORD");
 
-        // let token_balance = record.balance;
-        // let token_exit_fee = record.balance * EXIT_FEE;
-
         self.total_weight -= record.denorm;
         
         let index = record.index;
         self.tokens.swap_remove(index);
         self.records.remove(token_account_id);
 
-        // token(token_account_id).transfer(env::predecessor_account(), token_balance - token_exit_fee)
+        // The last token took the place of the unbound token
+        if let Some(moved_token) = self.tokens.get(index) {
+            let mut moved_record = self.records.get(&moved_token).expect("ERR_NO_RECORD");
+            moved_record.index = index;
+            self.records.insert(&moved_token, &moved_record);
+        }
+
+        logger::log(Event::Unbind(UnbindEvent {
+            pool_id: self.id.into(),
+            token: token_account_id.to_string(),
+            balance: record.balance.into()
+        }));
+
+        record.balance
+    }
+
+    /**
+     * @notice adds tokens that were sent to the factory outside of the pool's methods to its balance
+     */
+    pub fn gulp(
+        &mut self,
+        token_account_id: &AccountId,
+        amount: u128
+    ) {
+        self.assert_unlocked();
+        self.update_oracle();
+        let mut record = self.records.get(token_account_id).expect("ERR_NO_RECORD");
+        record.balance += amount;
+        self.records.insert(token_account_id, &record);
     }
 
-    // TODO: Gulp function requires async balance checks, will only work when pools are sharded
+    /**
+     * @notice takes `protocol_fee` of the swap fee that was charged on `token_amount_in` out of the pool's balance
+     * @return the amount that was withheld for the factory
+     */
+    pub fn withhold_protocol_fee(
+        &mut self,
+        token_in: &AccountId,
+        token_amount_in: u128,
+        protocol_fee: u128
+    ) -> u128 {
+        self.update_oracle();
+        let swap_fee_amount = math::mul_u128(token_amount_in, self.get_swap_fee());
+        let protocol_fee_amount = math::mul_u128(swap_fee_amount, protocol_fee);
+        if protocol_fee_amount == 0 {
+            return 0;
+        }
+
+        let mut record = self.records.get(token_in).expect("ERR_NO_RECORD");
+        record.balance -= protocol_fee_amount;
+        self.records.insert(token_in, &record);
+
+        protocol_fee_amount
+    }
+
+    /**
+     * @return the amount of `token_out` that a swap of `token_amount_in` pays out at the current weights,
+     * `None` if the pool can't take the trade
+     */
+    pub fn quote_exact_amount_in(
+        &self,
+        token_in: &AccountId,
+        token_amount_in: u128,
+        token_out: &AccountId
+    ) -> Option<u128> {
+        self.calc_swap_exact_amount_in(token_in, token_amount_in, token_out, 0, u128::MAX)
+            .ok()
+            .map(|swap| swap.token_amount_out.into())
+    }
 
     pub fn get_spot_price(
         &self, 
@@ -244,8 +1124,10 @@ impl Pool {
         assert!(self.is_bound(token_out), "ERR_NOT_BOUND");
         let record_in = self.records.get(token_in).expect("ERR_NO_RECORD");
         let record_out = self.records.get(token_out).expect("ERR_NO_RECORD");
+        let weight_in = self.get_denormalized_weight(token_in);
+        let weight_out = self.get_denormalized_weight(token_out);
 
-        math::calc_spot_price(record_in.balance, record_in.denorm, record_out.balance, record_out.denorm, self.swap_fee)
+        math::calc_spot_price(record_in.balance, weight_in, record_out.balance, weight_out, self.get_swap_fee())
     }
 
     pub fn get_spot_price_sans_fee(
@@ -257,140 +1139,679 @@ impl Pool {
         assert!(self.is_bound(token_out), "ERR_NOT_BOUND");
         let record_in = self.records.get(token_in).expect("ERR_NO_RECORD");
         let record_out = self.records.get(token_out).expect("ERR_NO_RECORD");
+        let weight_in = self.get_denormalized_weight(token_in);
+        let weight_out = self.get_denormalized_weight(token_out);
 
-        math::calc_spot_price(record_in.balance, record_in.denorm, record_out.balance, record_out.denorm, 0)
+        math::calc_spot_price(record_in.balance, weight_in, record_out.balance, weight_out, 0)
     }
 
-    pub fn join_pool(
-        &mut self,
-        sender: &AccountId,
+    /**
+     * @return the amount of every token that has to be added to mint `pool_amount_out` pool tokens
+     */
+    pub fn calc_join_pool(
+        &self,
         pool_amount_out: u128,
-        max_amounts_in: Vec<U128>
-    ) {
-        assert!(self.finalized, "ERR_NOT_FINALIZED");
-        assert_eq!(max_amounts_in.len() as u64, self.get_num_tokens(), "ERR_AMOUNTS_LEN");
+        max_amounts_in: &[U128]
+    ) -> PoolResult<Vec<u128>> {
+        self.check_unlocked()?;
+        self.price_join_pool(pool_amount_out, max_amounts_in)
+    }
+
+    // Prices a join regardless of the lock, which the join itself already holds
+    fn price_join_pool(
+        &self,
+        pool_amount_out: u128,
+        max_amounts_in: &[U128]
+    ) -> PoolResult<Vec<u128>> {
+        ensure(self.finalized, PoolError::NotFinalized)?;
+        ensure(!self.paused, PoolError::Paused)?;
+        ensure(max_amounts_in.len() as u64 == self.get_num_tokens(), PoolError::AmountsLen)?;
 
         let pool_total = self.token.total_supply();
         let ratio = math::div_u128(pool_amount_out, pool_total);
-        assert_ne!(ratio, 0, "ERR_MATH_APPROX");
+        ensure(ratio != 0, PoolError::MathApprox)?;
+
+        let mut amounts_in = Vec::with_capacity(max_amounts_in.len());
 
         for (i, token) in self.tokens.iter().enumerate() {
-            let mut record = self.records
+            let balance = self.records
                 .get(&token)
-                .expect("ERR_NO_RECORD");
+                .expect("ERR_NO_RECORD")
+                .balance;
 
-            let balance = record.balance;
-            
             let token_amount_in = math::mul_u128(ratio, balance);
 
-            assert_ne!(token_amount_in, 0, "ERR_MATH_APPROX");
-            assert!(token_amount_in <= u128::from(max_amounts_in[i]), "ERR_LIMIT_IN");
+            ensure(token_amount_in != 0, PoolError::MathApprox)?;
+            ensure(token_amount_in <= u128::from(max_amounts_in[i]), PoolError::LimitIn)?;
 
+            amounts_in.push(token_amount_in);
+        }
+
+        Ok(amounts_in)
+    }
+
+    /**
+     * @notice adds the amounts every token is joined with to the pool's balances
+     * @dev locks the pool, `pool_amount_out` is only minted by `mint_joined` once the factory received every token
+     * @return the amount of every token that has to be pulled from `sender`
+     */
+    pub fn join_pool(
+        &mut self,
+        sender: &AccountId,
+        pool_amount_out: u128,
+        max_amounts_in: Vec<U128>
+    ) -> Vec<u128> {
+        self.lock();
+        assert!(self.is_whitelisted_lp(sender), "ERR_NOT_WHITELISTED");
+        self.update_oracle();
+        self.poke_weights();
+        let amounts_in = expect_ok(self.price_join_pool(pool_amount_out, &max_amounts_in));
+
+        for (token, token_amount_in) in self.tokens.iter().zip(amounts_in.iter()) {
+            let mut record = self.records
+                .get(&token)
+                .expect("ERR_NO_RECORD");
             record.balance += token_amount_in;
-            
             self.records.insert(&token, &record);
-
-            // TODO: Transfer tokens in from user
         }
 
-        self.token.mint(pool_amount_out, sender);
+        logger::log(Event::Join(JoinEvent {
+            pool_id: self.id.into(),
+            caller: sender.to_string(),
+            tokens_in: self.to_token_amounts(&amounts_in),
+            pool_tokens_minted: pool_amount_out.into()
+        }));
+
+        amounts_in
     }
 
-    pub fn exit_pool(
+    pub fn join_swap_extern_amount_in(
         &mut self,
         sender: &AccountId,
-        pool_amount_in: u128,
-        min_amounts_out: Vec<U128>
-    ) {
+        token_in: &AccountId,
+        token_amount_in: u128,
+        min_pool_amount_out: u128
+    ) -> u128 {
+        self.lock();
+        assert!(self.is_whitelisted_lp(sender), "ERR_NOT_WHITELISTED");
         assert!(self.finalized, "ERR_NOT_FINALIZED");
-        assert_eq!(min_amounts_out.len() as u64, self.get_num_tokens(), "ERR_AMOUNTS_LEN");
+        assert!(!self.paused, "ERR_PAUSED");
+        self.update_oracle();
+        self.poke_weights();
+        assert!(self.is_bound(token_in), "ERR_NOT_BOUND");
+
+        let mut in_record = self.records.get(token_in).expect("ERR_NO_RECORD");
+
+        assert!(token_amount_in <= math::mul_u128(in_record.balance, MAX_IN_RATIO), "ERR_MAX_IN_RATIO");
+
+        let pool_amount_out = math::calc_pool_out_given_single_in(
+            in_record.balance,
+            in_record.denorm,
+            self.token.total_supply(),
+            self.total_weight,
+            token_amount_in,
+            self.get_swap_fee()
+        );
+
+        assert!(pool_amount_out >= min_pool_amount_out, "ERR_LIMIT_OUT");
+
+        in_record.balance += token_amount_in;
+        self.records.insert(token_in, &in_record);
+
+        self.log_single_join(sender, token_in, token_amount_in, pool_amount_out);
+
+        pool_amount_out
+    }
+
+    pub fn join_swap_pool_amount_out(
+        &mut self,
+        sender: &AccountId,
+        token_in: &AccountId,
+        pool_amount_out: u128,
+        max_amount_in: u128
+    ) -> u128 {
+        self.lock();
+        assert!(self.is_whitelisted_lp(sender), "ERR_NOT_WHITELISTED");
+        assert!(self.finalized, "ERR_NOT_FINALIZED");
+        assert!(!self.paused, "ERR_PAUSED");
+        self.update_oracle();
+        self.poke_weights();
+        assert!(self.is_bound(token_in), "ERR_NOT_BOUND");
+
+        let mut in_record = self.records.get(token_in).expect("ERR_NO_RECORD");
+
+        let token_amount_in = math::calc_single_in_given_pool_out(
+            in_record.balance,
+            in_record.denorm,
+            self.token.total_supply(),
+            self.total_weight,
+            pool_amount_out,
+            self.get_swap_fee()
+        );
+
+        assert_ne!(token_amount_in, 0, "ERR_MATH_APPROX");
+        assert!(token_amount_in <= max_amount_in, "ERR_LIMIT_IN");
+        assert!(token_amount_in <= math::mul_u128(in_record.balance, MAX_IN_RATIO), "ERR_MAX_IN_RATIO");
+
+        in_record.balance += token_amount_in;
+        self.records.insert(token_in, &in_record);
+
+        self.log_single_join(sender, token_in, token_amount_in, pool_amount_out);
+
+        token_amount_in
+    }
+
+    /**
+     * @return the amount of every token that's paid out for burning `pool_amount_in` pool tokens
+     */
+    pub fn calc_exit_pool(
+        &self,
+        pool_amount_in: u128,
+        min_amounts_out: &[U128]
+    ) -> PoolResult<Vec<u128>> {
+        self.check_unlocked()?;
+        ensure(self.finalized, PoolError::NotFinalized)?;
+        ensure(min_amounts_out.len() as u64 == self.get_num_tokens(), PoolError::AmountsLen)?;
 
         let pool_total = self.token.total_supply();
-        let exit_fee = math::mul_u128(pool_amount_in, EXIT_FEE);
+        let exit_fee = math::mul_u128(pool_amount_in, self.exit_fee);
         let pool_amount_in_min_exit_fee = pool_amount_in - exit_fee;
         let ratio = math::div_u128(pool_amount_in_min_exit_fee, pool_total);
-        assert_ne!(ratio, 0, "ERR_MATH_APPROX");
+        ensure(ratio != 0, PoolError::MathApprox)?;
+
+        let mut amounts_out = Vec::with_capacity(min_amounts_out.len());
 
-        // Make sure this method is called by on_vault_received I think? 
-        // Burn `pool_amount_in` pool_tokens 
-        // TODO: Temp burn fn
-        self.token.faux_burn(pool_amount_in);
         for (i, token) in self.tokens.iter().enumerate() {
-            let mut record = self.records
+            let balance = self.records
                 .get(&token)
-                .expect("ERR_NO_RECORD");
-            let balance = record.balance;
+                .expect("ERR_NO_RECORD")
+                .balance;
             let token_amount_out = math::mul_u128(ratio, balance);
-            
-            assert_ne!(token_amount_out, 0, "ERR_MATH_APPROX");
-            assert!(token_amount_out >= u128::from(min_amounts_out[i]), "ERR_LIMIT_OUT");
 
-            record.balance -= token_amount_out;
+            ensure(token_amount_out != 0, PoolError::MathApprox)?;
+            ensure(token_amount_out >= u128::from(min_amounts_out[i]), PoolError::LimitOut)?;
+
+            amounts_out.push(token_amount_out);
+        }
+
+        Ok(amounts_out)
+    }
 
+    pub fn exit_pool(
+        &mut self,
+        sender: &AccountId,
+        fee_receiver: &AccountId,
+        pool_amount_in: u128,
+        min_amounts_out: Vec<U128>
+    ) -> Vec<u128> {
+        self.assert_unlocked();
+        self.update_oracle();
+        self.poke_weights();
+        let amounts_out = expect_ok(self.calc_exit_pool(pool_amount_in, &min_amounts_out));
+
+        let exit_fee = self.collect_exit_fee(sender, fee_receiver, pool_amount_in);
+        for (token, token_amount_out) in self.tokens.iter().zip(amounts_out.iter()) {
+            let mut record = self.records
+                .get(&token)
+                .expect("ERR_NO_RECORD");
+            record.balance -= token_amount_out;
             self.records.insert(&token, &record);
-            // Transfer token to user
         }
+
+        logger::log(Event::Exit(ExitEvent {
+            pool_id: self.id.into(),
+            caller: sender.to_string(),
+            tokens_out: self.to_token_amounts(&amounts_out),
+            pool_tokens_burned: (pool_amount_in - exit_fee).into(),
+            exit_fee: exit_fee.into()
+        }));
+
+        amounts_out
     }
 
-    pub fn swap_exact_amount_in(
+    pub fn exit_swap_pool_amount_in(
+        &mut self,
+        sender: &AccountId,
+        fee_receiver: &AccountId,
+        token_out: &AccountId,
+        pool_amount_in: u128,
+        min_amount_out: u128
+    ) -> u128 {
+        self.assert_unlocked();
+        assert!(self.finalized, "ERR_NOT_FINALIZED");
+        self.update_oracle();
+        self.poke_weights();
+        assert!(self.is_bound(token_out), "ERR_NOT_BOUND");
+
+        let mut out_record = self.records.get(token_out).expect("ERR_NO_RECORD");
+
+        let token_amount_out = math::calc_single_out_given_pool_in(
+            out_record.balance,
+            out_record.denorm,
+            self.token.total_supply(),
+            self.total_weight,
+            pool_amount_in,
+            self.get_swap_fee(),
+            self.exit_fee
+        );
+
+        assert!(token_amount_out >= min_amount_out, "ERR_LIMIT_OUT");
+        assert!(token_amount_out <= math::mul_u128(out_record.balance, MAX_OUT_RATIO), "ERR_MAX_OUT_RATIO");
+
+        out_record.balance -= token_amount_out;
+        self.records.insert(token_out, &out_record);
+
+        let exit_fee = self.collect_exit_fee(sender, fee_receiver, pool_amount_in);
+
+        self.log_single_exit(sender, token_out, token_amount_out, pool_amount_in, exit_fee);
+
+        token_amount_out
+    }
+
+    pub fn exit_swap_extern_amount_out(
         &mut self,
         sender: &AccountId,
+        fee_receiver: &AccountId,
+        token_out: &AccountId,
+        token_amount_out: u128,
+        max_pool_amount_in: u128
+    ) -> u128 {
+        self.assert_unlocked();
+        assert!(self.finalized, "ERR_NOT_FINALIZED");
+        self.update_oracle();
+        self.poke_weights();
+        assert!(self.is_bound(token_out), "ERR_NOT_BOUND");
+
+        let mut out_record = self.records.get(token_out).expect("ERR_NO_RECORD");
+
+        assert!(token_amount_out <= math::mul_u128(out_record.balance, MAX_OUT_RATIO), "ERR_MAX_OUT_RATIO");
+
+        let pool_amount_in = math::calc_pool_in_given_single_out(
+            out_record.balance,
+            out_record.denorm,
+            self.token.total_supply(),
+            self.total_weight,
+            token_amount_out,
+            self.get_swap_fee(),
+            self.exit_fee
+        );
+
+        assert_ne!(pool_amount_in, 0, "ERR_MATH_APPROX");
+        assert!(pool_amount_in <= max_pool_amount_in, "ERR_LIMIT_IN");
+
+        out_record.balance -= token_amount_out;
+        self.records.insert(token_out, &out_record);
+
+        let exit_fee = self.collect_exit_fee(sender, fee_receiver, pool_amount_in);
+
+        self.log_single_exit(sender, token_out, token_amount_out, pool_amount_in, exit_fee);
+
+        pool_amount_in
+    }
+
+    /**
+     * @notice prices a swap of `token_amount_in` at the current weights, checking every limit the swap checks
+     */
+    pub fn calc_swap_exact_amount_in(
+        &self,
         token_in: &AccountId,
         token_amount_in: u128,
         token_out: &AccountId,
         min_amount_out: u128,
         max_price: u128
-    ) -> (u128, u128) {
-        assert!(self.is_bound(token_in), "ERR_NOT_BOUND");
-        assert!(self.is_bound(token_out), "ERR_NOT_BOUND");
-        assert!(self.finalized, "ERR_NOT_FINALIZED");
+    ) -> PoolResult<SwapPreview> {
+        self.check_unlocked()?;
+        self.price_swap_exact_amount_in(token_in, token_amount_in, token_out, min_amount_out, max_price)
+    }
 
-        let mut in_record = self.records.get(token_in).expect("ERR_NO_RECORD");
-        let mut out_record = self.records.get(token_out).expect("ERR_NO_RECORD");
+    fn price_swap_exact_amount_in(
+        &self,
+        token_in: &AccountId,
+        token_amount_in: u128,
+        token_out: &AccountId,
+        min_amount_out: u128,
+        max_price: u128
+    ) -> PoolResult<SwapPreview> {
+        ensure(self.is_bound(token_in), PoolError::NotBound)?;
+        ensure(self.is_bound(token_out), PoolError::NotBound)?;
+        ensure(self.public_swap, PoolError::SwapNotPublic)?;
+        ensure(!self.paused, PoolError::Paused)?;
+        ensure(token_amount_in > 0, PoolError::ZeroAmount)?;
 
-        assert!(token_amount_in <= math::mul_u128(in_record.balance, MAX_IN_RATIO), "ERR_MAX_IN_RATIO");
+        let balance_in = self.records.get(token_in).expect("ERR_NO_RECORD").balance;
+        let balance_out = self.records.get(token_out).expect("ERR_NO_RECORD").balance;
+        let weight_in = self.get_denormalized_weight(token_in);
+        let weight_out = self.get_denormalized_weight(token_out);
+
+        // Also keeps the base of the power in `calc_out_given_in` within the bounds `pow_u128` accepts
+        ensure(token_amount_in <= math::mul_u128(balance_in, MAX_IN_RATIO), PoolError::MaxInRatio)?;
 
         let spot_price_before = math::calc_spot_price(
-            in_record.balance, 
-            in_record.denorm, 
-            out_record.balance, 
-            out_record.denorm, 
-            self.swap_fee
+            balance_in, 
+            weight_in, 
+            balance_out, 
+            weight_out, 
+            self.get_swap_fee()
         );
         
-        assert!(spot_price_before <= max_price, "ERR_BAD_LIMIT_PRICE");
+        ensure(spot_price_before <= max_price, PoolError::BadLimitPrice)?;
         
         let token_amount_out = math::calc_out_given_in(
-            in_record.balance,
-            in_record.denorm,
-            out_record.balance,
-            out_record.denorm,
+            balance_in,
+            weight_in,
+            balance_out,
+            weight_out,
             token_amount_in, 
-            self.swap_fee
+            self.get_swap_fee()
         );
 
-        assert!(token_amount_out >= min_amount_out, "ERR_LIMIT_OUT");
-
-        in_record.balance += token_amount_in;
-        out_record.balance -= token_amount_out;
+        // An amount in too small to pay anything out would divide by zero below
+        ensure(token_amount_out > 0, PoolError::ZeroAmount)?;
+        ensure(token_amount_out >= min_amount_out, PoolError::LimitOut)?;
 
         let spot_price_after = math::calc_spot_price(
-            in_record.balance, 
-            in_record.denorm, 
-            out_record.balance, 
-            out_record.denorm, 
-            self.swap_fee
+            balance_in + token_amount_in, 
+            weight_in, 
+            balance_out - token_amount_out, 
+            weight_out, 
+            self.get_swap_fee()
         );
         
-        assert!(spot_price_after >= spot_price_before, "ERR_MATH_APPROX");
-        assert!(spot_price_after <= max_price, "ERR_LIMIT_PRICE");
-        assert!(spot_price_before <= math::div_u128(token_amount_in, token_amount_out), "ERR_MATH_APPROX");
+        ensure(spot_price_after >= spot_price_before, PoolError::MathApprox)?;
+        ensure(spot_price_after <= max_price, PoolError::LimitPrice)?;
+        ensure(spot_price_before <= math::div_u128(token_amount_in, token_amount_out), PoolError::MathApprox)?;
+
+        Ok(SwapPreview {
+            token_amount_in: token_amount_in.into(),
+            token_amount_out: token_amount_out.into(),
+            spot_price_before: spot_price_before.into(),
+            spot_price_after: spot_price_after.into()
+        })
+    }
+
+    pub fn swap_exact_amount_in(
+        &mut self,
+        sender: &AccountId,
+        token_in: &AccountId,
+        token_amount_in: u128,
+        token_out: &AccountId,
+        min_amount_out: u128,
+        max_price: u128
+    ) -> (u128, u128) {
+        self.lock();
+        self.update_oracle();
+        self.poke_weights();
+        let swap = expect_ok(self.price_swap_exact_amount_in(token_in, token_amount_in, token_out, min_amount_out, max_price));
+        self.apply_swap(sender, token_in, token_out, &swap);
+
+        (swap.token_amount_out.into(), swap.spot_price_after.into())
+    }
+
+    /**
+     * @notice prices a swap for `token_amount_out` at the current weights, checking every limit the swap checks
+     */
+    pub fn calc_swap_exact_amount_out(
+        &self,
+        token_in: &AccountId,
+        max_amount_in: u128,
+        token_out: &AccountId,
+        token_amount_out: u128,
+        max_price: u128
+    ) -> PoolResult<SwapPreview> {
+        self.check_unlocked()?;
+        self.price_swap_exact_amount_out(token_in, max_amount_in, token_out, token_amount_out, max_price)
+    }
+
+    fn price_swap_exact_amount_out(
+        &self,
+        token_in: &AccountId,
+        max_amount_in: u128,
+        token_out: &AccountId,
+        token_amount_out: u128,
+        max_price: u128
+    ) -> PoolResult<SwapPreview> {
+        ensure(self.is_bound(token_in), PoolError::NotBound)?;
+        ensure(self.is_bound(token_out), PoolError::NotBound)?;
+        ensure(self.public_swap, PoolError::SwapNotPublic)?;
+        ensure(!self.paused, PoolError::Paused)?;
+        ensure(token_amount_out > 0, PoolError::ZeroAmount)?;
+
+        let balance_in = self.records.get(token_in).expect("ERR_NO_RECORD").balance;
+        let balance_out = self.records.get(token_out).expect("ERR_NO_RECORD").balance;
+        let weight_in = self.get_denormalized_weight(token_in);
+        let weight_out = self.get_denormalized_weight(token_out);
+
+        // Also keeps the base of the power in `calc_in_given_out` within the bounds `pow_u128` accepts
+        ensure(token_amount_out <= math::mul_u128(balance_out, MAX_OUT_RATIO), PoolError::MaxOutRatio)?;
+
+        let spot_price_before = math::calc_spot_price(
+            balance_in, 
+            weight_in, 
+            balance_out, 
+            weight_out, 
+            self.get_swap_fee()
+        );
+
+        ensure(spot_price_before <= max_price, PoolError::BadLimitPrice)?;
+
+        let token_amount_in = math::calc_in_given_out(
+            balance_in,
+            weight_in,
+            balance_out,
+            weight_out,
+            token_amount_out, 
+            self.get_swap_fee()
+        );
+
+        ensure(token_amount_in > 0, PoolError::ZeroAmount)?;
+        ensure(token_amount_in <= max_amount_in, PoolError::LimitIn)?;
+
+        let spot_price_after = math::calc_spot_price(
+            balance_in + token_amount_in, 
+            weight_in, 
+            balance_out - token_amount_out, 
+            weight_out, 
+            self.get_swap_fee()
+        );
+
+        ensure(spot_price_after >= spot_price_before, PoolError::MathApprox)?;
+        ensure(spot_price_after <= max_price, PoolError::LimitPrice)?;
+        ensure(spot_price_before <= math::div_u128(token_amount_in, token_amount_out), PoolError::MathApprox)?;
+
+        Ok(SwapPreview {
+            token_amount_in: token_amount_in.into(),
+            token_amount_out: token_amount_out.into(),
+            spot_price_before: spot_price_before.into(),
+            spot_price_after: spot_price_after.into()
+        })
+    }
+
+    pub fn swap_exact_amount_out(
+        &mut self,
+        sender: &AccountId,
+        token_in: &AccountId,
+        max_amount_in: u128,
+        token_out: &AccountId,
+        token_amount_out: u128,
+        max_price: u128
+    ) -> (u128, u128) {
+        self.lock();
+        self.update_oracle();
+        self.poke_weights();
+        let swap = expect_ok(self.price_swap_exact_amount_out(token_in, max_amount_in, token_out, token_amount_out, max_price));
+        self.apply_swap(sender, token_in, token_out, &swap);
+
+        (swap.token_amount_in.into(), swap.spot_price_after.into())
+    }
+
+    fn apply_swap(
+        &mut self,
+        sender: &AccountId,
+        token_in: &AccountId,
+        token_out: &AccountId,
+        swap: &SwapPreview
+    ) {
+        let mut in_record = self.records.get(token_in).expect("ERR_NO_RECORD");
+        let mut out_record = self.records.get(token_out).expect("ERR_NO_RECORD");
+
+        in_record.balance += u128::from(swap.token_amount_in);
+        out_record.balance -= u128::from(swap.token_amount_out);
 
-        // TODO: LOG swap
-        // TODO: transfer in_token from sender to contract
-        // TODO: transfer out_token from contract to sender
         self.records.insert(&token_in, &in_record);
         self.records.insert(&token_out, &out_record);
-        (token_amount_out, spot_price_after)
+
+        let base_fee = self.get_base_swap_fee();
+        if let Some(dynamic_fee) = self.dynamic_fee.as_mut() {
+            dynamic_fee.record_price_move(base_fee, swap.spot_price_before.into(), swap.spot_price_after.into(), env::block_timestamp());
+        }
+
+        self.log_swap(
+            sender,
+            token_in,
+            token_out,
+            swap.token_amount_in.into(),
+            swap.token_amount_out.into(),
+            swap.spot_price_before.into(),
+            swap.spot_price_after.into()
+        );
+    }
+
+    /*** LOGGING HELPERS ***/
+
+    fn to_token_amounts(&self, amounts: &[u128]) -> Vec<TokenAmount> {
+        self.tokens
+            .iter()
+            .zip(amounts.iter())
+            .map(|(token, amount)| TokenAmount { token, amount: U128(*amount) })
+            .collect()
+    }
+
+    fn log_swap(
+        &self,
+        sender: &AccountId,
+        token_in: &AccountId,
+        token_out: &AccountId,
+        token_amount_in: u128,
+        token_amount_out: u128,
+        spot_price_before: u128,
+        spot_price_after: u128
+    ) {
+        logger::log(Event::Swap(SwapEvent {
+            pool_id: self.id.into(),
+            caller: sender.to_string(),
+            token_in: token_in.to_string(),
+            token_out: token_out.to_string(),
+            token_amount_in: token_amount_in.into(),
+            token_amount_out: token_amount_out.into(),
+            spot_price_before: spot_price_before.into(),
+            spot_price_after: spot_price_after.into(),
+            swap_fee: self.get_swap_fee().into()
+        }));
+    }
+
+    fn log_single_join(
+        &self,
+        sender: &AccountId,
+        token_in: &AccountId,
+        token_amount_in: u128,
+        pool_amount_out: u128
+    ) {
+        logger::log(Event::Join(JoinEvent {
+            pool_id: self.id.into(),
+            caller: sender.to_string(),
+            tokens_in: vec![TokenAmount { token: token_in.to_string(), amount: token_amount_in.into() }],
+            pool_tokens_minted: pool_amount_out.into()
+        }));
+    }
+
+    fn log_single_exit(
+        &self,
+        sender: &AccountId,
+        token_out: &AccountId,
+        token_amount_out: u128,
+        pool_amount_in: u128,
+        exit_fee: u128
+    ) {
+        logger::log(Event::Exit(ExitEvent {
+            pool_id: self.id.into(),
+            caller: sender.to_string(),
+            tokens_out: vec![TokenAmount { token: token_out.to_string(), amount: token_amount_out.into() }],
+            pool_tokens_burned: (pool_amount_in - exit_fee).into(),
+            exit_fee: exit_fee.into()
+        }));
+    }
+
+    /**
+     * @notice moves the exit fee share of `pool_amount_in` to `fee_receiver` and burns the rest
+     * @return the pool tokens that were paid as exit fee
+     */
+    fn collect_exit_fee(
+        &mut self,
+        sender: &AccountId,
+        fee_receiver: &AccountId,
+        pool_amount_in: u128
+    ) -> u128 {
+        let exit_fee = math::mul_u128(pool_amount_in, self.exit_fee);
+        if exit_fee > 0 && sender != fee_receiver {
+            self.token.transfer(sender, fee_receiver, exit_fee);
+        }
+        self.token.burn(sender, pool_amount_in - exit_fee);
+        exit_fee
+    }
+
+    /**
+     * @notice reverts a balance increase after the transfer into the factory failed
+     * @dev leaves the oracle alone, it last accumulated the committed prices before the pool was locked
+     * and the next update accumulates the restored ones as if the operation never happened.
+     * Drops the surcharge a rolled back swap added to the dynamic fee
+     */
+    pub fn rollback_transfer_in(
+        &mut self,
+        token_account_id: &AccountId,
+        amount: u128
+    ) {
+        self.rollback_price_move();
+        if let Some(mut record) = self.records.get(token_account_id) {
+            record.balance -= amount;
+            self.records.insert(token_account_id, &record);
+        }
+    }
+
+    /**
+     * @notice reverts a balance decrease after the transfer out of the factory failed, the tokens are still in custody
+     * @dev leaves the oracle alone and drops the swap's surcharge like `rollback_transfer_in`
+     * @return false if `token_account_id` was unbound in the meantime and the balance could not be restored
+     */
+    pub fn rollback_transfer_out(
+        &mut self,
+        token_account_id: &AccountId,
+        amount: u128
+    ) -> bool {
+        self.rollback_price_move();
+        match self.records.get(token_account_id) {
+            Some(mut record) => {
+                record.balance += amount;
+                self.records.insert(token_account_id, &record);
+                true
+            },
+            None => false
+        }
+    }
+
+    fn rollback_price_move(&mut self) {
+        if let Some(dynamic_fee) = self.dynamic_fee.as_mut() {
+            dynamic_fee.rollback();
+        }
+    }
+
+    /**
+     * @notice mints the pool tokens of a join once all of its transfers into the factory succeeded
+     */
+    pub fn mint_joined(
+        &mut self,
+        account_id: &AccountId,
+        amount: u128
+    ) {
+        self.token.mint(amount, account_id);
     }
 }
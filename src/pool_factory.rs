@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use near_sdk::{
     near_bindgen,
     json_types::{
@@ -5,9 +6,20 @@ use near_sdk::{
         U64
     },
     AccountId, 
+    Gas,
     env,
+    ext_contract,
+    Promise,
+    PromiseResult,
+    serde_json,
+    serde::{
+        Serialize,
+        Deserialize
+    },
     collections::{
-        UnorderedMap
+        UnorderedMap,
+        UnorderedSet,
+        LookupMap
     },
     borsh::{
         BorshDeserialize,
@@ -15,14 +27,175 @@ use near_sdk::{
     }
 };
 
-use crate::pool::Pool;
+use crate::pool::{
+    Pool,
+    PoolInfo,
+    PendingSwapFee,
+    DynamicFeeInfo,
+    PoolError,
+    PoolResult,
+    SwapPreview
+};
+use crate::constants::{
+    MAX_PROTOCOL_FEE,
+    MAX_TOTAL_WEIGHT,
+    MIN_WEIGHT,
+    TOKEN_DENOM
+};
+use crate::token::{
+    VaultId,
+    FungibleTokenMetadata
+};
+use crate::logger::{
+    self,
+    Event,
+    NewPoolEvent,
+    NewOwnerEvent,
+    TransferFailedEvent,
+    GulpEvent,
+    PauseEvent,
+    ProtocolFeeSetEvent
+};
+
+const GAS_FOR_FT_TRANSFER: Gas = 10_000_000_000_000;
+const GAS_FOR_RESOLVE_TRANSFER: Gas = 10_000_000_000_000;
+const GAS_FOR_POOL_TOKEN_TRANSFER_CALL: Gas = GAS_FOR_RESOLVE_TRANSFER + 5_000_000_000_000;
+const GAS_FOR_FLASH_SWAP_CALLBACK: Gas = 50_000_000_000_000;
+
+// The amount of a quoted trade is allocated to routes in this many parts
+const ROUTE_SPLIT_PARTS: u128 = 10;
+const MAX_ROUTE_HOPS: u64 = 3;
+
+#[ext_contract(ext_fungible_token)]
+trait FungibleToken {
+    fn transfer_from(&mut self, owner_id: AccountId, new_owner_id: AccountId, amount: U128);
+    fn transfer(&mut self, new_owner_id: AccountId, amount: U128);
+    fn withdraw_from_vault(&mut self, vault_id: VaultId, receiver_id: AccountId, amount: U128);
+    fn get_balance(&self, owner_id: AccountId) -> U128;
+}
+
+/// NEP-141 style receiver of pool tokens, `pool_id` identifies which pool's tokens were received
+#[ext_contract(ext_pool_token_receiver)]
+trait PoolTokenReceiver {
+    fn ft_on_transfer(&mut self, pool_id: U64, sender_id: AccountId, amount: U128, msg: String) -> U128;
+}
+
+/// Receiver of a flash swap, has to let the factory pull `token_amount_in` of `token_in` before it returns
+#[ext_contract(ext_flash_swap_receiver)]
+trait FlashSwapReceiver {
+    fn on_flash_swap(&mut self, pool_id: U64, sender_id: AccountId, token_in: AccountId, token_amount_in: U128, token_out: AccountId, token_amount_out: U128, msg: String);
+}
+
+#[ext_contract(ext_self)]
+trait ExtSelf {
+    fn resolve_pool_token_transfer(&mut self, pool_id: U64, sender_id: AccountId, receiver_id: AccountId, amount: U128) -> U128;
+    fn resolve_transfer_in(&mut self, pool_id: U64, token_account_id: AccountId, amount: U128);
+    fn resolve_transfer_out(&mut self, pool_id: U64, token_account_id: AccountId, receiver_id: AccountId, amount: U128);
+    fn resolve_join(&mut self, pool_id: U64, sender: AccountId, tokens: Vec<AccountId>, amounts_in: Vec<U128>, pool_amount_out: U128);
+    fn resolve_outcome_pool(&mut self, pool_id: U64, sender: AccountId, tokens: Vec<AccountId>);
+    fn resolve_swap(&mut self, pool_id: U64, sender: AccountId, token_in: AccountId, token_amount_in: U128, token_out: AccountId, token_amount_out: U128, protocol_fee_amount: U128);
+    fn resolve_collect_protocol_fee(&mut self, token_account_id: AccountId, amount: U128);
+    fn resolve_refund(&mut self, token_account_id: AccountId, receiver_id: AccountId, amount: U128);
+    fn resolve_gulp(&mut self, pool_id: U64, token_account_id: AccountId);
+    fn resolve_batch_swap(&mut self, sender: AccountId, token_in: AccountId, token_amount_in: U128, token_out: AccountId, swaps: Vec<ExecutedSwap>, payouts: Vec<(U64, U128)>);
+    fn resolve_flash_swap_transfer(&mut self, sender: AccountId, receiver_id: AccountId, swap: ExecutedSwap, msg: String);
+    fn resolve_flash_swap(&mut self, receiver_id: AccountId, swap: ExecutedSwap);
+}
+
+/**
+ * @notice a single leg of a multi-hop swap
+ */
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SwapStep {
+    pub pool_id: U64,
+    pub token_in: AccountId,
+    pub token_out: AccountId
+}
+
+/**
+ * @notice the part of a split trade that's routed through `route`
+ */
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RouteAmount {
+    pub route: Vec<SwapStep>,
+    pub token_amount_in: U128
+}
+
+/**
+ * @notice a quoted part of a split trade, can be passed to `split_swap_exact_in` as a `RouteAmount`
+ */
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RouteQuote {
+    pub route: Vec<SwapStep>,
+    pub token_amount_in: U128,
+    pub token_amount_out: U128
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SplitQuote {
+    pub routes: Vec<RouteQuote>,
+    pub total_amount_out: U128
+}
+
+/**
+ * @notice the outcome of a leg that was applied to its pool optimistically, kept to roll it back if the pull fails
+ */
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ExecutedSwap {
+    pub pool_id: U64,
+    pub token_in: AccountId,
+    pub token_out: AccountId,
+    pub token_amount_in: U128,
+    pub token_amount_out: U128,
+    pub protocol_fee_amount: U128
+}
+
+/**
+ * @notice actions that can be attached as `payload` to a token transfer into the factory
+ * @dev amounts that aren't used by the action stay in the vault and are refunded by the token contract
+ */
+#[derive(Deserialize)]
+#[serde(crate = "near_sdk::serde", tag = "action", rename_all = "snake_case")]
+pub enum VaultAction {
+    // Swaps the received tokens, exact in unless `token_amount_out` is set
+    Swap {
+        pool_id: U64,
+        token_out: AccountId,
+        min_amount_out: Option<U128>,
+        token_amount_out: Option<U128>,
+        max_price: U128
+    },
+    // Single-asset join, exact in unless `pool_amount_out` is set
+    Join {
+        pool_id: U64,
+        min_pool_amount_out: Option<U128>,
+        pool_amount_out: Option<U128>
+    },
+    // Controller only, adds the received token to its balance before finalization, the token has to be bound already
+    AddLiquidity {
+        pool_id: U64,
+        denorm: U128
+    }
+}
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct PoolFactory {
     owner: AccountId, // The owner of the contract
     nonce: u64, // Incrementing number that's used to define a pool's id
-    pools: UnorderedMap<u64, Pool> // Maps pool ids to pool
+    pools: UnorderedMap<u64, Pool>, // Maps pool ids to pool
+    custody: LookupMap<AccountId, u128>, // Maps token account ids to the amount the factory holds on behalf of all pools
+    protocol_fee: u128, // Fraction of every swap fee that's withheld for the owner
+    protocol_fees: LookupMap<AccountId, u128>, // Maps token account ids to the protocol fees that can be collected
+    claimable: LookupMap<(AccountId, AccountId), u128>, // Maps account and token account id pairs to payouts that failed and can be claimed
+    token_pools: LookupMap<AccountId, UnorderedSet<u64>>, // Maps token account ids to the pools the token is bound to
+    pair_pools: LookupMap<(AccountId, AccountId), UnorderedSet<u64>>, // Maps sorted token pairs to the pools both tokens are bound to
+    paused: bool // Emergency pause of swaps and joins in every pool
 }
 
 /** 
@@ -50,7 +223,14 @@ impl PoolFactory {
         Self {
             owner: owner,
             nonce: 0,
-            pools: UnorderedMap::new(b"pools".to_vec())
+            pools: UnorderedMap::new(b"pools".to_vec()),
+            custody: LookupMap::new(b"custody".to_vec()),
+            protocol_fee: 0,
+            protocol_fees: LookupMap::new(b"protocol_fees".to_vec()),
+            claimable: LookupMap::new(b"claimable".to_vec()),
+            token_pools: LookupMap::new(b"tp".to_vec()),
+            pair_pools: LookupMap::new(b"pp".to_vec()),
+            paused: false
         }
     }
 
@@ -99,6 +279,62 @@ impl PoolFactory {
         pool.is_finalized()
     }
 
+    pub fn get_pool_controller(&self, pool_id: U64) -> AccountId {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.get_controller()
+    }
+
+    /**
+     * @return the account that was proposed as controller and hasn't accepted yet
+     */
+    pub fn get_pool_pending_controller(&self, pool_id: U64) -> Option<AccountId> {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.get_pending_controller()
+    }
+
+    pub fn pool_lp_whitelist_enabled(&self, pool_id: U64) -> bool {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.is_lp_whitelist_enabled()
+    }
+
+    /**
+     * @return true if `account_id` can join `pool_id`, always true for pools without a whitelist
+     */
+    pub fn pool_is_whitelisted_lp(&self, pool_id: U64, account_id: &AccountId) -> bool {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.is_whitelisted_lp(account_id)
+    }
+
+    /**
+     * @return at most `limit` whitelisted accounts of `pool_id` starting at `from_index`
+     */
+    pub fn get_pool_lp_whitelist(&self, pool_id: U64, from_index: U64, limit: U64) -> Vec<AccountId> {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.get_lp_whitelist(from_index.into(), limit.into())
+    }
+
+    pub fn pool_is_public_swap(&self, pool_id: U64) -> bool {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.is_public_swap()
+    }
+
+    /**
+     * @return true if swaps and joins of `pool_id` are paused, either for the pool itself or for every pool
+     */
+    pub fn pool_is_paused(&self, pool_id: U64) -> bool {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        self.paused || pool.is_paused()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pool_is_locked(&self, pool_id: U64) -> bool {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.is_locked()
+    }
+
     pub fn pool_token_is_bound(&self, token_account_id: &AccountId, pool_id: U64) -> bool {
         let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
         pool.records
@@ -142,157 +378,2188 @@ impl PoolFactory {
     }
 
     /**
-     * @notice allows the previous owner to set a new owner
-     * @param new_owner the `account_id` of the new owner
-     * @panics if the signer of this tx is not the previous owner
-     * @panics if `new_owner` is not a valid account id
+     * @return the fraction of the pool tokens of every exit from `pool_id` that's paid to the owner
      */
-    pub fn set_owner(&mut self, new_owner: AccountId) {
-        assert_eq!(env::predecessor_account_id(), self.owner, "ERR_NOT_OWNER");
-        assert!(env::is_valid_account_id(new_owner.as_bytes()), "ERR_INVALID_ACCOUNT_ID");
-        
-        self.owner = new_owner;
+    pub fn get_pool_exit_fee(&self, pool_id: U64) -> U128 {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.get_exit_fee().into()
     }
 
     /**
-     * @notice creates new token pool
-     * @param
-     * @return the new pool's id 
-     */ 
-    pub fn new_pool(&mut self, swap_fee: U128) -> U64 {
-        self.nonce += 1;
-        let new_pool = Pool::new(env::predecessor_account_id(), self.nonce, u128::from(swap_fee));
-        self.pools.insert(&self.nonce, &new_pool);
-        self.nonce.into()
+     * @return the swap fee that's scheduled for `pool_id` and when it takes effect, `None` if there's none pending
+     */
+    pub fn get_pool_pending_swap_fee(&self, pool_id: U64) -> Option<PendingSwapFee> {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.get_pending_swap_fee()
     }
 
+    /**
+     * @return the dynamic fee policy of `pool_id` and its current surcharge, `None` if the pool has a static fee
+     */
+    pub fn get_pool_dynamic_fee(&self, pool_id: U64) -> Option<DynamicFeeInfo> {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.get_dynamic_fee()
+    }
 
-    /*** POOL SETTERS ***/
-
-    pub fn finalize_pool(&mut self, pool_id: U64) {
-        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
-        pool.finalize(&env::predecessor_account_id());
-        self.pools.insert(&pool_id.into(), &pool);
+    /**
+     * @notice time-weighted average price of `token_in` in `token_out` over the last `window_seconds`, without fees
+     * @dev the pool keeps `MAX_OBSERVATIONS` observations `OBSERVATION_PERIOD` apart, older windows may not be covered
+     */
+    pub fn get_twap(
+        &self,
+        pool_id: U64,
+        token_in: &AccountId,
+        token_out: &AccountId,
+        window_seconds: U64
+    ) -> U128 {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        let window = u64::from(window_seconds).checked_mul(1_000_000_000).expect("ERR_INVALID_WINDOW");
+        pool.get_twap(token_in, token_out, window).into()
     }
 
-    pub fn bind_pool(
-        &mut self, 
+    /**
+     * @notice previews `swap_exact_amount_in` without slippage limits, the amounts match the swap exactly
+     * @return the swap's amounts and spot prices or the error the swap would fail with
+     */
+    pub fn get_pool_out_given_in(
+        &self,
         pool_id: U64,
-        token_account_id: &AccountId,
-        denorm: U128,
-        balance: U128
-    ) {
-        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
-        pool.bind(
-            &env::predecessor_account_id(),
-            token_account_id,
-            u128::from(denorm),
-            u128::from(balance)
-        );
-        self.pools.insert(&pool_id.into(), &pool);
+        token_in: &AccountId,
+        token_amount_in: U128,
+        token_out: &AccountId
+    ) -> PoolResult<SwapPreview> {
+        let pool = self.pools.get(&pool_id.into()).ok_or(PoolError::NoPool)?;
+        self.check_not_paused()?;
+        pool.calc_swap_exact_amount_in(token_in, token_amount_in.into(), token_out, 0, u128::MAX)
     }
 
-    pub fn rebind_pool(
-        &mut self, 
+    /**
+     * @notice previews `swap_exact_amount_out` without slippage limits, the amounts match the swap exactly
+     * @return the swap's amounts and spot prices or the error the swap would fail with
+     */
+    pub fn get_pool_in_given_out(
+        &self,
         pool_id: U64,
-        token_account_id: &AccountId,
-        denorm: U128,
-        balance: U128
-    ) {
-        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
-        pool.rebind(
-            &env::predecessor_account_id(),
-            token_account_id,
-            u128::from(denorm),
-            u128::from(balance)
-        );
-        self.pools.insert(&pool_id.into(), &pool);
+        token_in: &AccountId,
+        token_out: &AccountId,
+        token_amount_out: U128
+    ) -> PoolResult<SwapPreview> {
+        let pool = self.pools.get(&pool_id.into()).ok_or(PoolError::NoPool)?;
+        self.check_not_paused()?;
+        pool.calc_swap_exact_amount_out(token_in, u128::MAX, token_out, token_amount_out.into(), u128::MAX)
     }
 
-    pub fn unbind_pool(
-        &mut self, 
+    /**
+     * @return the amount of every token, in order of `get_pool_current_tokens`, that `join_pool` takes for `pool_amount_out`
+     */
+    pub fn preview_join_pool(
+        &self,
         pool_id: U64,
-        token_account_id: &AccountId,
-    ) {
-        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
-        pool.unbind(
-            &env::predecessor_account_id(),
-            token_account_id
-        );
-        self.pools.insert(&pool_id.into(), &pool);
+        pool_amount_out: U128
+    ) -> PoolResult<Vec<U128>> {
+        let pool = self.pools.get(&pool_id.into()).ok_or(PoolError::NoPool)?;
+        self.check_not_paused()?;
+        let max_amounts_in = vec![U128(u128::MAX); pool.get_num_tokens() as usize];
+        let amounts_in = pool.calc_join_pool(pool_amount_out.into(), &max_amounts_in)?;
+        Ok(amounts_in.into_iter().map(U128).collect())
     }
 
-    pub fn get_pool_spot_price(
+    /**
+     * @return the amount of every token, in order of `get_pool_current_tokens`, that `exit_pool` pays out for `pool_amount_in`
+     */
+    pub fn preview_exit_pool(
         &self,
         pool_id: U64,
-        token_in: &AccountId,
-        token_out: &AccountId,
-    ) -> U128 {
+        pool_amount_in: U128
+    ) -> PoolResult<Vec<U128>> {
+        let pool = self.pools.get(&pool_id.into()).ok_or(PoolError::NoPool)?;
+        let min_amounts_out = vec![U128(0); pool.get_num_tokens() as usize];
+        let amounts_out = pool.calc_exit_pool(pool_amount_in.into(), &min_amounts_out)?;
+        Ok(amounts_out.into_iter().map(U128).collect())
+    }
+
+    /**
+     * @return a snapshot of `pool_id` with the weight and balance of every bound token
+     */
+    pub fn get_pool_info(&self, pool_id: U64) -> PoolInfo {
         let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
-        pool.get_spot_price(token_in, token_out).into()
+        pool.get_info()
     }
 
-    pub fn get_pool_spot_price_sans_fee(
+    /**
+     * @return snapshots of at most `limit` pools starting at `from_index`, in order of creation
+     */
+    pub fn get_pools(&self, from_index: U64, limit: U64) -> Vec<PoolInfo> {
+        let pools = self.pools.values_as_vector();
+        let from_index = u64::from(from_index);
+        let to_index = from_index.saturating_add(limit.into()).min(pools.len());
+        (from_index..to_index)
+            .map(|index| pools.get(index).expect("ERR_NO_POOL").get_info())
+            .collect()
+    }
+
+    /**
+     * @notice lists the pools `token_account_id` is bound to, including pools that aren't finalized yet
+     * @return at most `limit` pool ids starting at `from_index`
+     */
+    pub fn get_pools_for_token(
         &self,
-        pool_id: U64,
-        token_in: &AccountId,
-        token_out: &AccountId,
-    ) -> U128 {
+        token_account_id: &AccountId,
+        from_index: U64,
+        limit: U64
+    ) -> Vec<U64> {
+        match self.token_pools.get(token_account_id) {
+            Some(pools) => paginate(&pools, from_index.into(), limit.into()),
+            None => vec![]
+        }
+    }
+
+    /**
+     * @notice lists the pools both `token_a` and `token_b` are bound to, the order of the tokens doesn't matter
+     * @return at most `limit` pool ids starting at `from_index`
+     */
+    pub fn get_pools_for_pair(
+        &self,
+        token_a: &AccountId,
+        token_b: &AccountId,
+        from_index: U64,
+        limit: U64
+    ) -> Vec<U64> {
+        match self.pair_pools.get(&pair_key(token_a, token_b)) {
+            Some(pools) => paginate(&pools, from_index.into(), limit.into()),
+            None => vec![]
+        }
+    }
+
+    /**
+     * @return the weight of `token_account_id` in `pool_id` at the current block timestamp
+     */
+    pub fn get_pool_denormalized_weight(&self, pool_id: U64, token_account_id: &AccountId) -> U128 {
         let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
-        pool.get_spot_price_sans_fee(token_in, token_out).into()
+        pool.get_denormalized_weight(token_account_id).into()
     }
 
-    pub fn join_pool(
+    pub fn get_pool_total_denormalized_weight(&self, pool_id: U64) -> U128 {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.get_total_denormalized_weight().into()
+    }
+
+    /**
+     * @return the amount of `token_account_id` that's accounted to pools, including transfers in flight
+     */
+    pub fn get_custody_balance(&self, token_account_id: &AccountId) -> U128 {
+        self.custody.get(token_account_id).unwrap_or(0).into()
+    }
+
+    /**
+     * @return the fraction of each swap fee that accrues to the owner, denominated in 1e18
+     */
+    pub fn get_protocol_fee(&self) -> U128 {
+        self.protocol_fee.into()
+    }
+
+    /**
+     * @return the protocol fees in `token_account_id` that haven't been collected yet
+     */
+    pub fn get_accrued_protocol_fees(&self, token_account_id: &AccountId) -> U128 {
+        self.protocol_fees.get(token_account_id).unwrap_or(0).into()
+    }
+
+    /**
+     * @return the amount of `token_account_id` that failed to be paid out to `account_id` and can be claimed
+     */
+    pub fn get_claimable(&self, account_id: &AccountId, token_account_id: &AccountId) -> U128 {
+        self.claimable.get(&(account_id.to_string(), token_account_id.to_string())).unwrap_or(0).into()
+    }
+
+    /*** POOL TOKEN ***/
+
+    pub fn ft_total_supply(&self, pool_id: U64) -> U128 {
+        self.get_pool_token_total_supply(pool_id)
+    }
+
+    pub fn ft_balance_of(&self, pool_id: U64, account_id: AccountId) -> U128 {
+        self.get_pool_token_balance(pool_id, &account_id)
+    }
+
+    pub fn ft_metadata(&self, pool_id: U64) -> FungibleTokenMetadata {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.get_pool_token_metadata()
+    }
+
+    /**
+     * @notice transfers `amount` of `pool_id`'s pool tokens from the predecessor to `receiver_id`
+     * @dev requires exactly one yoctoNEAR so the transfer has to be signed with a full access key
+     */
+    #[payable]
+    pub fn transfer(
         &mut self,
         pool_id: U64,
-        pool_amount_out: U128,
-        max_amounts_in: Vec<U128>,
+        receiver_id: AccountId,
+        amount: U128
     ) {
+        assert_one_yocto();
         let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
-        pool.join_pool(
-            &env::predecessor_account_id(), 
-            pool_amount_out.into(),
-            max_amounts_in
-        );
+        pool.transfer_pool_tokens(&env::predecessor_account_id(), &receiver_id, amount.into());
         self.pools.insert(&pool_id.into(), &pool);
     }
 
-    pub fn exit_pool(
+    /**
+     * @notice transfers pool tokens to a contract and notifies it through `ft_on_transfer`
+     * @dev the receiver returns the amount it didn't use, which is refunded in `resolve_pool_token_transfer`,
+     *      requires exactly one yoctoNEAR like `transfer`
+     * @return promise resolving to the amount of pool tokens that was used by the receiver
+     */
+    #[payable]
+    pub fn transfer_call(
         &mut self,
         pool_id: U64,
-        pool_amount_in: U128,
-        min_amounts_out: Vec<U128>,
-    ) {
+        receiver_id: AccountId,
+        amount: U128,
+        msg: String
+    ) -> Promise {
+        assert_one_yocto();
+        let sender_id = env::predecessor_account_id();
         let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
-        pool.exit_pool(
-            &env::predecessor_account_id(), 
-            pool_amount_in.into(),
-            min_amounts_out
-        );
+        pool.transfer_pool_tokens(&sender_id, &receiver_id, amount.into());
         self.pools.insert(&pool_id.into(), &pool);
+
+        let gas_to_receiver = env::prepaid_gas().saturating_sub(GAS_FOR_POOL_TOKEN_TRANSFER_CALL + GAS_FOR_RESOLVE_TRANSFER);
+
+        ext_pool_token_receiver::ft_on_transfer(
+            pool_id,
+            sender_id.to_string(),
+            amount,
+            msg,
+            &receiver_id,
+            0,
+            gas_to_receiver
+        ).then(ext_self::resolve_pool_token_transfer(
+            pool_id,
+            sender_id,
+            receiver_id,
+            amount,
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_TRANSFER
+        ))
     }
 
-    pub fn swap_exact_amount_in(
-        &mut self, 
-        pool_id: U64,
-        token_in: &AccountId,
-        token_amount_in: U128,
-        token_out: &AccountId,
-        min_amount_out: U128,
-        max_price: U128
-    ) -> (U128, U128) {
-        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
-        let (token_amount_out, spot_price_after) = pool.swap_exact_amount_in(
-            &env::predecessor_account_id(),
-            token_in,
-            token_amount_in.into(),
-            token_out,
-            min_amount_out.into(),
-            max_price.into()
+    /**
+     * @notice allows the previous owner to set a new owner
+     * @param new_owner the `account_id` of the new owner
+     * @panics if the signer of this tx is not the previous owner
+     * @panics if `new_owner` is not a valid account id
+     */
+    pub fn set_owner(&mut self, new_owner: AccountId) {
+        assert_eq!(env::predecessor_account_id(), self.owner, "ERR_NOT_OWNER");
+        assert!(env::is_valid_account_id(new_owner.as_bytes()), "ERR_INVALID_ACCOUNT_ID");
+
+        logger::log(Event::NewOwner(NewOwnerEvent {
+            previous_owner: self.owner.to_string(),
+            new_owner: new_owner.to_string()
+        }));
+        
+        self.owner = new_owner;
+    }
+
+    /**
+     * @notice sets the fraction of each swap fee that's withheld from the pools for the owner
+     * @param protocol_fee the new protocol fee, denominated in 1e18
+     * @panics if the signer of this tx is not the owner
+     * @panics if `protocol_fee` exceeds `MAX_PROTOCOL_FEE`
+     */
+    pub fn set_protocol_fee(&mut self, protocol_fee: U128) {
+        assert_eq!(env::predecessor_account_id(), self.owner, "ERR_NOT_OWNER");
+        let protocol_fee = u128::from(protocol_fee);
+        assert!(protocol_fee <= MAX_PROTOCOL_FEE, "ERR_MAX_PROTOCOL_FEE");
+
+        self.protocol_fee = protocol_fee;
+
+        logger::log(Event::ProtocolFeeSet(ProtocolFeeSetEvent {
+            protocol_fee: protocol_fee.into()
+        }));
+    }
+
+    /**
+     * @notice pauses or unpauses swaps and joins in every pool, exits keep working
+     * @panics if the signer of this tx is not the owner
+     */
+    pub fn set_paused(&mut self, paused: bool) {
+        assert_eq!(env::predecessor_account_id(), self.owner, "ERR_NOT_OWNER");
+        self.paused = paused;
+
+        logger::log(Event::Pause(PauseEvent {
+            pool_id: None,
+            paused
+        }));
+    }
+
+    /**
+     * @notice pauses or unpauses swaps and joins in `pool_id`, exits keep working
+     * @panics if the signer of this tx is not the owner
+     */
+    pub fn set_pool_paused(&mut self, pool_id: U64, paused: bool) {
+        assert_eq!(env::predecessor_account_id(), self.owner, "ERR_NOT_OWNER");
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.set_paused(paused);
+        self.pools.insert(&pool_id.into(), &pool);
+    }
+
+    /**
+     * @notice releases the lock of a pool whose callback never ran, e.g. because it ran out of gas
+     * @panics if the signer of this tx is not the owner
+     */
+    pub fn unlock_pool(&mut self, pool_id: U64) {
+        assert_eq!(env::predecessor_account_id(), self.owner, "ERR_NOT_OWNER");
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.unlock();
+        self.pools.insert(&pool_id.into(), &pool);
+    }
+
+    /**
+     * @notice transfers the accrued protocol fees of each token in `token_account_ids` to the owner
+     * @panics if the signer of this tx is not the owner
+     */
+    pub fn collect_protocol_fees(&mut self, token_account_ids: Vec<AccountId>) {
+        assert_eq!(env::predecessor_account_id(), self.owner, "ERR_NOT_OWNER");
+
+        for token_account_id in token_account_ids {
+            let amount = self.protocol_fees.get(&token_account_id).unwrap_or(0);
+            if amount == 0 {
+                continue;
+            }
+            self.protocol_fees.insert(&token_account_id, &0);
+
+            ext_fungible_token::transfer(
+                self.owner.to_string(),
+                U128(amount),
+                &token_account_id,
+                0,
+                GAS_FOR_FT_TRANSFER
+            ).then(ext_self::resolve_collect_protocol_fee(
+                token_account_id.to_string(),
+                U128(amount),
+                &env::current_account_id(),
+                0,
+                GAS_FOR_RESOLVE_TRANSFER
+            ));
+        }
+    }
+
+    /**
+     * @notice transfers the payouts of `token_account_id` that failed earlier to the signer of this tx
+     * @panics if there's nothing to claim
+     */
+    pub fn claim(&mut self, token_account_id: AccountId) -> Promise {
+        let sender = env::predecessor_account_id();
+        let key = (sender.to_string(), token_account_id.to_string());
+        let amount = self.claimable.get(&key).unwrap_or(0);
+        assert!(amount > 0, "ERR_NOTHING_TO_CLAIM");
+        self.claimable.remove(&key);
+
+        self.refund_underlying(&token_account_id, &sender, amount)
+    }
+
+    /**
+     * @notice creates new token pool
+     * @param
+     * @return the new pool's id 
+     */ 
+    pub fn new_pool(&mut self, swap_fee: U128) -> U64 {
+        self.nonce += 1;
+        let new_pool = Pool::new(env::predecessor_account_id(), self.nonce, u128::from(swap_fee));
+        self.pools.insert(&self.nonce, &new_pool);
+
+        logger::log(Event::NewPool(NewPoolEvent {
+            pool_id: self.nonce.into(),
+            controller: env::predecessor_account_id(),
+            swap_fee
+        }));
+
+        self.nonce.into()
+    }
+
+    /**
+     * @notice creates a pool of `outcome_tokens` and `collateral_token` priced at `initial_odds`,
+     *      it's finalized by `resolve_outcome_pool` once every token was pulled from the sender
+     * @dev the collateral gets half of the total weight and each outcome its odds of the other half,
+     *      so the odds only hold as prices if every token starts with the same balance
+     * @param initial_odds odds of each outcome denominated in 1e18, they need to add up to 1e18
+     *      and each needs to be at least 4% so its weight isn't below `MIN_WEIGHT`
+     * @param balance the amount of the collateral and of each outcome token that's pulled from the sender,
+     *      it sets the pool's depth but not its prices
+     * @return the new pool's id
+     */
+    pub fn new_outcome_pool(
+        &mut self,
+        collateral_token: AccountId,
+        outcome_tokens: Vec<AccountId>,
+        initial_odds: Vec<U128>,
+        swap_fee: U128,
+        balance: U128
+    ) -> U64 {
+        assert_eq!(outcome_tokens.len(), initial_odds.len(), "ERR_ODDS_LEN");
+        assert!(outcome_tokens.len() >= 2, "ERR_MIN_OUTCOMES");
+        let odds_sum = initial_odds.iter().fold(0, |sum, odds| sum + u128::from(*odds));
+        assert_eq!(odds_sum, TOKEN_DENOM, "ERR_INVALID_ODDS");
+
+        // The collateral's weight already is as large as it can be, so lower odds can't be priced
+        let outcome_weight = MAX_TOTAL_WEIGHT / 2;
+        let min_odds = MIN_WEIGHT * TOKEN_DENOM / outcome_weight;
+        assert!(initial_odds.iter().all(|odds| u128::from(*odds) >= min_odds), "ERR_MIN_ODDS");
+
+        let sender = env::predecessor_account_id();
+        let pool_id = self.new_pool(swap_fee);
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+
+        let mut tokens = vec![(collateral_token, outcome_weight)];
+        for (outcome_token, odds) in outcome_tokens.into_iter().zip(initial_odds) {
+            // odds sum to 1e18 so the outcome weights sum to exactly `outcome_weight`
+            tokens.push((outcome_token, u128::from(odds) * (outcome_weight / TOKEN_DENOM)));
+        }
+
+        for (token_account_id, denorm) in &tokens {
+            pool.bind(&sender, token_account_id, *denorm, balance.into());
+        }
+        // Nothing can change the pool until it's finalized or torn down
+        pool.lock();
+        self.pools.insert(&pool_id.into(), &pool);
+
+        let tokens: Vec<AccountId> = tokens.into_iter().map(|(token_account_id, _)| token_account_id).collect();
+        let mut pulls: Option<Promise> = None;
+        for token_account_id in &tokens {
+            self.increase_custody(token_account_id, balance.into());
+            let pull = transfer_from(token_account_id, &sender, balance.into());
+            pulls = Some(match pulls {
+                Some(promise) => promise.and(pull),
+                None => pull
+            });
+        }
+
+        // Leave enough gas in the callback to refund every token if the pool has to be torn down
+        let gas_for_resolve = GAS_FOR_RESOLVE_TRANSFER + (GAS_FOR_FT_TRANSFER + GAS_FOR_RESOLVE_TRANSFER) * tokens.len() as u64;
+
+        pulls.expect("ERR_NO_TOKENS").then(ext_self::resolve_outcome_pool(
+            pool_id,
+            sender,
+            tokens,
+            &env::current_account_id(),
+            0,
+            gas_for_resolve
+        ));
+
+        pool_id
+    }
+
+
+    /*** POOL SETTERS ***/
+
+    /**
+     * @notice finalizes `pool_id` and indexes it under its final set of tokens
+     */
+    pub fn finalize_pool(&mut self, pool_id: U64) {
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.finalize(&env::predecessor_account_id());
+        self.index_pool(pool_id.into(), &pool);
+        self.pools.insert(&pool_id.into(), &pool);
+    }
+
+    /**
+     * @notice proposes `new_controller` as the controller of `pool_id`, it takes over once it calls `accept_controller`
+     */
+    pub fn set_controller(&mut self, pool_id: U64, new_controller: AccountId) {
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.set_controller(&env::predecessor_account_id(), &new_controller);
+        self.pools.insert(&pool_id.into(), &pool);
+    }
+
+    pub fn accept_controller(&mut self, pool_id: U64) {
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.accept_controller(&env::predecessor_account_id());
+        self.pools.insert(&pool_id.into(), &pool);
+    }
+
+    /**
+     * @notice only lets the controller and whitelisted accounts join `pool_id` while `enabled`, controller only
+     */
+    pub fn set_lp_whitelist_enabled(&mut self, pool_id: U64, enabled: bool) {
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.set_lp_whitelist_enabled(&env::predecessor_account_id(), enabled);
+        self.pools.insert(&pool_id.into(), &pool);
+    }
+
+    pub fn add_to_lp_whitelist(&mut self, pool_id: U64, account_ids: Vec<AccountId>) {
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.add_to_lp_whitelist(&env::predecessor_account_id(), account_ids);
+        self.pools.insert(&pool_id.into(), &pool);
+    }
+
+    pub fn remove_from_lp_whitelist(&mut self, pool_id: U64, account_ids: Vec<AccountId>) {
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.remove_from_lp_whitelist(&env::predecessor_account_id(), account_ids);
+        self.pools.insert(&pool_id.into(), &pool);
+    }
+
+    /**
+     * @notice sets the swap fee of `pool_id`, controller only
+     * @dev takes effect right away before the pool is finalized, `SWAP_FEE_UPDATE_DELAY` after this call otherwise
+     */
+    pub fn set_swap_fee(&mut self, pool_id: U64, swap_fee: U128) {
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.set_swap_fee(&env::predecessor_account_id(), swap_fee.into());
+        self.pools.insert(&pool_id.into(), &pool);
+    }
+
+    /**
+     * @notice sets the exit fee of a pool that isn't finalized yet, controller only
+     * @dev exit fees are paid in pool tokens to the owner's LP balance
+     */
+    pub fn set_exit_fee(&mut self, pool_id: U64, exit_fee: U128) {
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.set_exit_fee(&env::predecessor_account_id(), exit_fee.into());
+        self.pools.insert(&pool_id.into(), &pool);
+    }
+
+    /**
+     * @notice lets the swap fee of `pool_id` rise with price moves up to `max_fee`, controller only
+     * @param sensitivity fee surcharge per unit of relative price move, denominated in `TOKEN_DENOM`
+     * @param decay_period nanoseconds it takes a surcharge to decay back to the base fee
+     */
+    pub fn set_dynamic_fee(
+        &mut self,
+        pool_id: U64,
+        max_fee: U128,
+        sensitivity: U128,
+        decay_period: U64
+    ) {
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.set_dynamic_fee(
+            &env::predecessor_account_id(),
+            Some((max_fee.into(), sensitivity.into(), decay_period.into()))
         );
+        self.pools.insert(&pool_id.into(), &pool);
+    }
 
+    /**
+     * @notice drops the dynamic fee of `pool_id` and its surcharge, controller only
+     */
+    pub fn disable_dynamic_fee(&mut self, pool_id: U64) {
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.set_dynamic_fee(&env::predecessor_account_id(), None);
         self.pools.insert(&pool_id.into(), &pool);
+    }
 
-        (token_amount_out.into(), spot_price_after.into())
+    /**
+     * @notice opens or closes swaps of a pool that isn't finalized yet, controller only
+     */
+    pub fn set_public_swap(&mut self, pool_id: U64, public_swap: bool) {
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.set_public_swap(&env::predecessor_account_id(), public_swap);
+        self.pools.insert(&pool_id.into(), &pool);
+    }
+
+    /**
+     * @notice binds a new token to the pool and pulls `balance` of it from the controller
+     * @dev the controller needs to have given the factory an allowance on `token_account_id`,
+     * the pool is locked until the pull resolves so it can't be finalized or traded against an unfunded balance
+     */
+    pub fn bind_pool(
+        &mut self, 
+        pool_id: U64,
+        token_account_id: &AccountId,
+        denorm: U128,
+        balance: U128
+    ) {
+        let sender = env::predecessor_account_id();
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.bind(
+            &sender,
+            token_account_id,
+            u128::from(denorm),
+            u128::from(balance)
+        );
+        pool.lock();
+        self.index_token(pool_id.into(), &pool, token_account_id);
+        self.pools.insert(&pool_id.into(), &pool);
+
+        self.pull_underlying(pool_id, token_account_id, &sender, balance.into());
+    }
+
+    /**
+     * @notice updates the weight and balance of a bound token, pulling or pushing the balance difference
+     * @dev the pool is locked until a pull resolves, like in `bind_pool`
+     */
+    pub fn rebind_pool(
+        &mut self, 
+        pool_id: U64,
+        token_account_id: &AccountId,
+        denorm: U128,
+        balance: U128
+    ) {
+        let sender = env::predecessor_account_id();
+        let balance = u128::from(balance);
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        let old_balance = pool.records.get(token_account_id).map_or(0, |record| record.balance);
+        pool.rebind(
+            &sender,
+            token_account_id,
+            u128::from(denorm),
+            balance
+        );
+        if balance > old_balance {
+            pool.lock();
+        }
+        self.pools.insert(&pool_id.into(), &pool);
+
+        match balance.cmp(&old_balance) {
+            Ordering::Greater => {
+                self.pull_underlying(pool_id, token_account_id, &sender, balance - old_balance);
+            },
+            Ordering::Less => {
+                self.push_underlying(pool_id, token_account_id, &sender, old_balance - balance);
+            },
+            Ordering::Equal => ()
+        };
+    }
+
+    /**
+     * @notice schedules a gradual weight change of a finalized pool, used to run liquidity bootstrapping pools
+     * @param start_time block timestamp in nanoseconds at which the weights start moving from their current values,
+     *      it can't be in the past
+     * @param end_time block timestamp in nanoseconds at which `end_weights` are reached, at least an hour after `start_time`
+     */
+    pub fn update_weights_gradually(
+        &mut self,
+        pool_id: U64,
+        tokens: Vec<AccountId>,
+        end_weights: Vec<U128>,
+        start_time: U64,
+        end_time: U64
+    ) {
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.update_weights_gradually(
+            &env::predecessor_account_id(),
+            tokens,
+            end_weights.into_iter().map(u128::from).collect(),
+            start_time.into(),
+            end_time.into()
+        );
+        self.pools.insert(&pool_id.into(), &pool);
+    }
+
+    /**
+     * @notice unbinds a token from the pool and returns its balance to the controller
+     */
+    pub fn unbind_pool(
+        &mut self, 
+        pool_id: U64,
+        token_account_id: &AccountId,
+    ) {
+        let sender = env::predecessor_account_id();
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        let balance = pool.unbind(
+            &sender,
+            token_account_id
+        );
+        self.unindex_token(pool_id.into(), &pool, token_account_id);
+        self.pools.insert(&pool_id.into(), &pool);
+
+        self.push_underlying(pool_id, token_account_id, &sender, balance);
+    }
+
+    pub fn get_pool_spot_price(
+        &self,
+        pool_id: U64,
+        token_in: &AccountId,
+        token_out: &AccountId,
+    ) -> U128 {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.get_spot_price(token_in, token_out).into()
+    }
+
+    pub fn get_pool_spot_price_sans_fee(
+        &self,
+        pool_id: U64,
+        token_in: &AccountId,
+        token_out: &AccountId,
+    ) -> U128 {
+        let pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.get_spot_price_sans_fee(token_in, token_out).into()
+    }
+
+    pub fn join_pool(
+        &mut self,
+        pool_id: U64,
+        pool_amount_out: U128,
+        max_amounts_in: Vec<U128>,
+    ) {
+        let sender = env::predecessor_account_id();
+        self.assert_not_paused();
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        let amounts_in = pool.join_pool(
+            &sender, 
+            pool_amount_out.into(),
+            max_amounts_in
+        );
+        let tokens = pool.get_current_tokens();
+        self.pools.insert(&pool_id.into(), &pool);
+
+        self.pull_for_join(pool_id, &sender, tokens, amounts_in, pool_amount_out.into());
     }
-}
\ No newline at end of file
+
+    pub fn join_swap_extern_amount_in(
+        &mut self,
+        pool_id: U64,
+        token_in: &AccountId,
+        token_amount_in: U128,
+        min_pool_amount_out: U128
+    ) -> U128 {
+        let sender = env::predecessor_account_id();
+        self.assert_not_paused();
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        let pool_amount_out = pool.join_swap_extern_amount_in(
+            &sender,
+            token_in,
+            token_amount_in.into(),
+            min_pool_amount_out.into()
+        );
+        self.pools.insert(&pool_id.into(), &pool);
+
+        self.pull_for_join(pool_id, &sender, vec![token_in.to_string()], vec![token_amount_in.into()], pool_amount_out);
+
+        pool_amount_out.into()
+    }
+
+    pub fn join_swap_pool_amount_out(
+        &mut self,
+        pool_id: U64,
+        token_in: &AccountId,
+        pool_amount_out: U128,
+        max_amount_in: U128
+    ) -> U128 {
+        let sender = env::predecessor_account_id();
+        self.assert_not_paused();
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        let token_amount_in = pool.join_swap_pool_amount_out(
+            &sender,
+            token_in,
+            pool_amount_out.into(),
+            max_amount_in.into()
+        );
+        self.pools.insert(&pool_id.into(), &pool);
+
+        self.pull_for_join(pool_id, &sender, vec![token_in.to_string()], vec![token_amount_in], pool_amount_out.into());
+
+        token_amount_in.into()
+    }
+
+    pub fn exit_pool(
+        &mut self,
+        pool_id: U64,
+        pool_amount_in: U128,
+        min_amounts_out: Vec<U128>,
+    ) {
+        let sender = env::predecessor_account_id();
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        let amounts_out = pool.exit_pool(
+            &sender, 
+            &self.owner,
+            pool_amount_in.into(),
+            min_amounts_out
+        );
+        let tokens = pool.get_current_tokens();
+        self.pools.insert(&pool_id.into(), &pool);
+
+        for (token, amount_out) in tokens.iter().zip(amounts_out) {
+            self.push_underlying(pool_id, token, &sender, amount_out);
+        }
+    }
+
+    pub fn exit_swap_pool_amount_in(
+        &mut self,
+        pool_id: U64,
+        token_out: &AccountId,
+        pool_amount_in: U128,
+        min_amount_out: U128
+    ) -> U128 {
+        let sender = env::predecessor_account_id();
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        let token_amount_out = pool.exit_swap_pool_amount_in(
+            &sender,
+            &self.owner,
+            token_out,
+            pool_amount_in.into(),
+            min_amount_out.into()
+        );
+        self.pools.insert(&pool_id.into(), &pool);
+
+        self.push_underlying(pool_id, token_out, &sender, token_amount_out);
+
+        token_amount_out.into()
+    }
+
+    pub fn exit_swap_extern_amount_out(
+        &mut self,
+        pool_id: U64,
+        token_out: &AccountId,
+        token_amount_out: U128,
+        max_pool_amount_in: U128
+    ) -> U128 {
+        let sender = env::predecessor_account_id();
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        let pool_amount_in = pool.exit_swap_extern_amount_out(
+            &sender,
+            &self.owner,
+            token_out,
+            token_amount_out.into(),
+            max_pool_amount_in.into()
+        );
+        self.pools.insert(&pool_id.into(), &pool);
+
+        self.push_underlying(pool_id, token_out, &sender, token_amount_out.into());
+
+        pool_amount_in.into()
+    }
+
+    pub fn swap_exact_amount_in(
+        &mut self, 
+        pool_id: U64,
+        token_in: &AccountId,
+        token_amount_in: U128,
+        token_out: &AccountId,
+        min_amount_out: U128,
+        max_price: U128
+    ) -> (U128, U128) {
+        let sender = env::predecessor_account_id();
+        self.assert_not_paused();
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        let (token_amount_out, spot_price_after) = pool.swap_exact_amount_in(
+            &sender,
+            token_in,
+            token_amount_in.into(),
+            token_out,
+            min_amount_out.into(),
+            max_price.into()
+        );
+        let protocol_fee_amount = pool.withhold_protocol_fee(token_in, token_amount_in.into(), self.protocol_fee);
+        self.pools.insert(&pool_id.into(), &pool);
+
+        self.pull_for_swap(pool_id, &sender, token_in, token_amount_in.into(), token_out, token_amount_out, protocol_fee_amount);
+
+        (token_amount_out.into(), spot_price_after.into())
+    }
+
+    pub fn swap_exact_amount_out(
+        &mut self, 
+        pool_id: U64,
+        token_in: &AccountId,
+        max_amount_in: U128,
+        token_out: &AccountId,
+        token_amount_out: U128,
+        max_price: U128
+    ) -> (U128, U128) {
+        let sender = env::predecessor_account_id();
+        self.assert_not_paused();
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        let (token_amount_in, spot_price_after) = pool.swap_exact_amount_out(
+            &sender,
+            token_in,
+            max_amount_in.into(),
+            token_out,
+            token_amount_out.into(),
+            max_price.into()
+        );
+        let protocol_fee_amount = pool.withhold_protocol_fee(token_in, token_amount_in, self.protocol_fee);
+        self.pools.insert(&pool_id.into(), &pool);
+
+        self.pull_for_swap(pool_id, &sender, token_in, token_amount_in, token_out, token_amount_out.into(), protocol_fee_amount);
+
+        (token_amount_in.into(), spot_price_after.into())
+    }
+
+    /**
+     * @notice swaps `token_amount_in` through every step of `route`, the output of a step is the input of the next
+     * @dev intermediate tokens never leave the factory, only the first token is pulled and the last one paid out
+     * @return the amount of the route's last token that's paid out
+     */
+    pub fn batch_swap_exact_in(
+        &mut self,
+        route: Vec<SwapStep>,
+        token_amount_in: U128,
+        min_total_out: U128
+    ) -> U128 {
+        let sender = env::predecessor_account_id();
+        assert_valid_route(&route);
+
+        let mut token_amount = u128::from(token_amount_in);
+        let mut swaps = Vec::with_capacity(route.len());
+        for step in &route {
+            let swap = self.internal_swap_exact_in(&sender, step, token_amount);
+            token_amount = swap.token_amount_out.into();
+            swaps.push(swap);
+        }
+        assert!(token_amount >= u128::from(min_total_out), "ERR_LIMIT_OUT");
+
+        let first = &route[0];
+        let last = &route[route.len() - 1];
+        let payouts = vec![(last.pool_id, U128(token_amount))];
+        self.pull_for_batch_swap(&sender, &first.token_in, token_amount_in.into(), &last.token_out, swaps, payouts);
+
+        token_amount.into()
+    }
+
+    /**
+     * @notice searches all pools open to swaps for the split of `token_amount_in` over routes of at most `max_hops` that pays out the most
+     * @dev the amount is allocated in `ROUTE_SPLIT_PARTS` parts, or one part per unit if it's smaller, each to the route with the highest marginal output.
+     * Routes that share a pool with a route already in use are skipped, so every part is priced exactly as it'll be executed
+     * @return an empty quote if there's no route that pays out for every part of `token_amount_in`
+     */
+    pub fn quote_best_route(
+        &self,
+        token_in: AccountId,
+        token_out: AccountId,
+        token_amount_in: U128,
+        max_hops: U64
+    ) -> SplitQuote {
+        self.assert_not_paused();
+        let max_hops = u64::from(max_hops);
+        assert!(max_hops > 0 && max_hops <= MAX_ROUTE_HOPS, "ERR_MAX_HOPS");
+        assert_ne!(token_in, token_out, "ERR_SAME_TOKEN");
+
+        let token_amount_in = u128::from(token_amount_in);
+        assert!(token_amount_in > 0, "ERR_ZERO_AMOUNT");
+
+        let routes = self.find_routes(&token_in, &token_out, max_hops);
+        let parts = ROUTE_SPLIT_PARTS.min(token_amount_in);
+        let part = token_amount_in / parts;
+
+        let mut amounts_in = vec![0; routes.len()];
+        let mut amounts_out = vec![0; routes.len()];
+        for i in 0..parts {
+            let part = if i == parts - 1 { token_amount_in - part * (parts - 1) } else { part };
+
+            let mut best: Option<(usize, u128, u128)> = None;
+            for (r, route) in routes.iter().enumerate() {
+                let overlaps = (0..routes.len()).any(|used| used != r && amounts_in[used] > 0 && routes_overlap(route, &routes[used]));
+                if overlaps {
+                    continue;
+                }
+
+                if let Some(amount_out) = self.quote_route(route, amounts_in[r] + part) {
+                    let gain = amount_out.saturating_sub(amounts_out[r]);
+                    if gain > 0 && best.map_or(true, |(_, best_gain, _)| gain > best_gain) {
+                        best = Some((r, gain, amount_out));
+                    }
+                }
+            }
+
+            // Quoting only the parts that could be routed would misstate the trade
+            let (r, _, amount_out) = match best {
+                Some(best) => best,
+                None => return SplitQuote {
+                    routes: vec![],
+                    total_amount_out: U128(0)
+                }
+            };
+            amounts_in[r] += part;
+            amounts_out[r] = amount_out;
+        }
+
+        let total_amount_out = amounts_out.iter().sum::<u128>();
+        let routes = routes
+            .into_iter()
+            .zip(amounts_in.into_iter().zip(amounts_out.into_iter()))
+            .filter(|(_, (amount_in, _))| *amount_in > 0)
+            .map(|(route, (amount_in, amount_out))| RouteQuote {
+                route,
+                token_amount_in: amount_in.into(),
+                token_amount_out: amount_out.into()
+            })
+            .collect();
+
+        SplitQuote {
+            routes,
+            total_amount_out: total_amount_out.into()
+        }
+    }
+
+    /**
+     * @notice swaps every part of a split trade, e.g. the routes returned by `quote_best_route`
+     * @dev all routes have to start and end with the same token, slippage is only checked against the total
+     * and routes can't share a pool, each pool is locked until the pull resolves
+     * @return the total amount of the last token that's paid out
+     */
+    pub fn split_swap_exact_in(
+        &mut self,
+        splits: Vec<RouteAmount>,
+        min_total_out: U128
+    ) -> U128 {
+        let sender = env::predecessor_account_id();
+        assert!(!splits.is_empty(), "ERR_EMPTY_ROUTE");
+
+        let token_in = splits[0].route.first().expect("ERR_EMPTY_ROUTE").token_in.to_string();
+        let token_out = splits[0].route.last().expect("ERR_EMPTY_ROUTE").token_out.to_string();
+
+        let mut total_in = 0;
+        let mut total_out = 0;
+        let mut swaps = vec![];
+        let mut payouts = Vec::with_capacity(splits.len());
+        for split in &splits {
+            assert_valid_route(&split.route);
+            let first = &split.route[0];
+            let last = &split.route[split.route.len() - 1];
+            assert_eq!(first.token_in, token_in, "ERR_INVALID_ROUTE");
+            assert_eq!(last.token_out, token_out, "ERR_INVALID_ROUTE");
+
+            let mut token_amount = u128::from(split.token_amount_in);
+            for step in &split.route {
+                let swap = self.internal_swap_exact_in(&sender, step, token_amount);
+                token_amount = swap.token_amount_out.into();
+                swaps.push(swap);
+            }
+
+            total_in += u128::from(split.token_amount_in);
+            total_out += token_amount;
+            payouts.push((last.pool_id, U128(token_amount)));
+        }
+        assert!(total_out >= u128::from(min_total_out), "ERR_LIMIT_OUT");
+
+        self.pull_for_batch_swap(&sender, &token_in, total_in, &token_out, swaps, payouts);
+
+        total_out.into()
+    }
+
+    /**
+     * @notice buys `token_amount_out` of the route's last token, the steps are priced from the last to the first
+     * @return the amount of the route's first token that's pulled from the sender
+     */
+    pub fn batch_swap_exact_out(
+        &mut self,
+        route: Vec<SwapStep>,
+        token_amount_out: U128,
+        max_total_in: U128
+    ) -> U128 {
+        let sender = env::predecessor_account_id();
+        assert_valid_route(&route);
+
+        let mut token_amount = u128::from(token_amount_out);
+        let mut swaps = Vec::with_capacity(route.len());
+        for step in route.iter().rev() {
+            let swap = self.internal_swap_exact_out(&sender, step, token_amount);
+            token_amount = swap.token_amount_in.into();
+            swaps.push(swap);
+        }
+        assert!(token_amount <= u128::from(max_total_in), "ERR_LIMIT_IN");
+
+        let first = &route[0];
+        let last = &route[route.len() - 1];
+        let payouts = vec![(last.pool_id, token_amount_out)];
+        self.pull_for_batch_swap(&sender, &first.token_in, token_amount, &last.token_out, swaps, payouts);
+
+        token_amount.into()
+    }
+
+    /**
+     * @notice sends `token_amount_out` to `receiver_id` before it's paid for, then calls `on_flash_swap` on the receiver
+     * and pulls `token_in` from it, priced like `swap_exact_amount_out` so the repayment includes the swap fee
+     * @dev the pool is locked until the repayment resolves, a receiver that doesn't repay only takes `token_out`
+     * out of the pool it was sent from
+     * @return the amount of `token_in` the receiver has to repay
+     */
+    pub fn flash_swap(
+        &mut self,
+        pool_id: U64,
+        token_in: &AccountId,
+        max_amount_in: U128,
+        token_out: &AccountId,
+        token_amount_out: U128,
+        receiver_id: AccountId,
+        msg: String
+    ) -> U128 {
+        let sender = env::predecessor_account_id();
+        self.assert_not_paused();
+        assert!(env::is_valid_account_id(receiver_id.as_bytes()), "ERR_INVALID_ACCOUNT_ID");
+
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        let (token_amount_in, _) = pool.swap_exact_amount_out(
+            &sender,
+            token_in,
+            max_amount_in.into(),
+            token_out,
+            token_amount_out.into(),
+            u128::MAX
+        );
+        let protocol_fee_amount = pool.withhold_protocol_fee(token_in, token_amount_in, self.protocol_fee);
+        self.pools.insert(&pool_id.into(), &pool);
+
+        let swap = ExecutedSwap {
+            pool_id,
+            token_in: token_in.to_string(),
+            token_out: token_out.to_string(),
+            token_amount_in: token_amount_in.into(),
+            token_amount_out,
+            protocol_fee_amount: protocol_fee_amount.into()
+        };
+
+        ext_fungible_token::transfer(
+            receiver_id.to_string(),
+            token_amount_out,
+            token_out,
+            0,
+            GAS_FOR_FT_TRANSFER
+        ).then(ext_self::resolve_flash_swap_transfer(
+            sender,
+            receiver_id,
+            swap,
+            msg,
+            &env::current_account_id(),
+            0,
+            GAS_FOR_FLASH_SWAP_CALLBACK + GAS_FOR_FT_TRANSFER + 2 * GAS_FOR_RESOLVE_TRANSFER
+        ));
+
+        token_amount_in.into()
+    }
+
+    /**
+     * @notice receives tokens through a vault and executes the `VaultAction` in `payload` with them
+     * @dev the predecessor is the token contract, only the amount the action needs is withdrawn from the vault
+     * @return the promise that withdraws from the vault and resolves the action
+     */
+    pub fn on_receive_with_vault(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        vault_id: VaultId,
+        payload: String
+    ) -> Promise {
+        let token_account_id = env::predecessor_account_id();
+        let amount = u128::from(amount);
+        let action: VaultAction = serde_json::from_str(&payload).expect("ERR_INVALID_PAYLOAD");
+
+        match action {
+            VaultAction::Swap { pool_id, token_out, min_amount_out, token_amount_out, max_price } => {
+                self.assert_not_paused();
+                let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+                let (token_amount_in, token_amount_out) = match token_amount_out {
+                    Some(token_amount_out) => {
+                        let (token_amount_in, _) = pool.swap_exact_amount_out(
+                            &sender_id,
+                            &token_account_id,
+                            amount,
+                            &token_out,
+                            token_amount_out.into(),
+                            max_price.into()
+                        );
+                        (token_amount_in, token_amount_out.into())
+                    },
+                    None => {
+                        let (token_amount_out, _) = pool.swap_exact_amount_in(
+                            &sender_id,
+                            &token_account_id,
+                            amount,
+                            &token_out,
+                            min_amount_out.map_or(0, u128::from),
+                            max_price.into()
+                        );
+                        (amount, token_amount_out)
+                    }
+                };
+                let protocol_fee_amount = pool.withhold_protocol_fee(&token_account_id, token_amount_in, self.protocol_fee);
+                self.pools.insert(&pool_id.into(), &pool);
+
+                let withdraw = withdraw_from_vault(vault_id, &token_account_id, token_amount_in);
+                self.then_resolve_swap(withdraw, pool_id, &sender_id, &token_account_id, token_amount_in, &token_out, token_amount_out, protocol_fee_amount)
+            },
+            VaultAction::Join { pool_id, min_pool_amount_out, pool_amount_out } => {
+                self.assert_not_paused();
+                let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+                let (token_amount_in, pool_amount_out) = match pool_amount_out {
+                    Some(pool_amount_out) => {
+                        let token_amount_in = pool.join_swap_pool_amount_out(
+                            &sender_id,
+                            &token_account_id,
+                            pool_amount_out.into(),
+                            amount
+                        );
+                        (token_amount_in, pool_amount_out.into())
+                    },
+                    None => {
+                        let pool_amount_out = pool.join_swap_extern_amount_in(
+                            &sender_id,
+                            &token_account_id,
+                            amount,
+                            min_pool_amount_out.map_or(0, u128::from)
+                        );
+                        (amount, pool_amount_out)
+                    }
+                };
+                self.pools.insert(&pool_id.into(), &pool);
+
+                let withdraw = withdraw_from_vault(vault_id, &token_account_id, token_amount_in);
+                self.then_resolve_join(withdraw, pool_id, &sender_id, vec![token_account_id], vec![token_amount_in], pool_amount_out)
+            },
+            VaultAction::AddLiquidity { pool_id, denorm } => {
+                let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+                // Any contract can call this, `sender_id` is only trusted once the predecessor is a token the controller bound
+                assert!(pool.is_bound(&token_account_id), "ERR_NOT_BOUND");
+                let balance = pool.get_balance(&token_account_id) + amount;
+                pool.rebind(&sender_id, &token_account_id, denorm.into(), balance);
+                // Locked until the deposit is withdrawn from the vault, like a rebind's pull
+                pool.lock();
+                self.pools.insert(&pool_id.into(), &pool);
+
+                let withdraw = withdraw_from_vault(vault_id, &token_account_id, amount);
+                self.then_resolve_transfer_in(withdraw, pool_id, &token_account_id, amount)
+            }
+        }
+    }
+
+    /**
+     * @notice absorbs tokens that were sent to the factory directly into `pool_id`'s balance of `token_account_id`
+     * @dev custody is shared by all pools, only the factory's balance exceeding what's accounted to pools is absorbed,
+     * the pool stays locked until the balance is known
+     * @panics if the predecessor is not the owner, the surplus can't be attributed to a pool so the owner decides which gets it
+     */
+    pub fn gulp(
+        &mut self,
+        pool_id: U64,
+        token_account_id: &AccountId
+    ) -> Promise {
+        assert_eq!(env::predecessor_account_id(), self.owner, "ERR_NOT_OWNER");
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        assert!(pool.is_bound(token_account_id), "ERR_NOT_BOUND");
+        pool.lock();
+        self.pools.insert(&pool_id.into(), &pool);
+
+        ext_fungible_token::get_balance(
+            env::current_account_id(),
+            token_account_id,
+            0,
+            GAS_FOR_FT_TRANSFER
+        ).then(ext_self::resolve_gulp(
+            pool_id,
+            token_account_id.to_string(),
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_TRANSFER
+        ))
+    }
+
+    /*** TRANSFER CALLBACKS ***/
+
+    /**
+     * @notice credits the surplus of the factory's token balance over its custody to the pool
+     * @dev unlocks the pool either way, nothing is absorbed if the balance couldn't be read
+     * @return the amount that was absorbed
+     */
+    pub fn resolve_gulp(
+        &mut self,
+        pool_id: U64,
+        token_account_id: AccountId
+    ) -> U128 {
+        assert_self();
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.unlock();
+        self.pools.insert(&pool_id.into(), &pool);
+
+        let balance: u128 = match env::promise_result(0) {
+            PromiseResult::Successful(value) => match serde_json::from_slice::<U128>(&value) {
+                Ok(balance) => balance.into(),
+                Err(_) => return U128(0)
+            },
+            _ => return U128(0)
+        };
+
+        let custody = self.custody.get(&token_account_id).unwrap_or(0);
+        // Pulls in flight are already part of custody and payouts only leave it once confirmed,
+        // so anything above custody was never accounted to any pool
+        if balance <= custody {
+            return U128(0);
+        }
+
+        let surplus = balance - custody;
+        pool.gulp(&token_account_id, surplus);
+        self.pools.insert(&pool_id.into(), &pool);
+        self.increase_custody(&token_account_id, surplus);
+
+        logger::log(Event::Gulp(GulpEvent {
+            pool_id,
+            token: token_account_id,
+            amount: surplus.into()
+        }));
+
+        U128(surplus)
+    }
+
+    /**
+     * @notice refunds the pool tokens the receiver of a `transfer_call` didn't use
+     * @return the amount of pool tokens that was used
+     */
+    pub fn resolve_pool_token_transfer(
+        &mut self,
+        pool_id: U64,
+        sender_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128
+    ) -> U128 {
+        assert_self();
+        let amount = u128::from(amount);
+
+        // A failed call or an invalid return value counts as nothing used
+        let unused_amount = match env::promise_result(0) {
+            PromiseResult::Successful(value) => {
+                match serde_json::from_slice::<U128>(&value) {
+                    Ok(unused_amount) => std::cmp::min(amount, unused_amount.into()),
+                    Err(_) => amount
+                }
+            },
+            _ => amount
+        };
+
+        if unused_amount == 0 {
+            return U128(amount);
+        }
+
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        // The receiver could have moved the tokens already, only refund what's left
+        let refund_amount = std::cmp::min(unused_amount, pool.get_pool_token_balance(&receiver_id));
+        if refund_amount > 0 {
+            pool.transfer_pool_tokens(&receiver_id, &sender_id, refund_amount);
+            self.pools.insert(&pool_id.into(), &pool);
+        }
+
+        U128(amount - refund_amount)
+    }
+
+    /**
+     * @notice rolls back the pool balance if pulling tokens into the factory failed
+     * @dev unlocks the pool either way
+     */
+    pub fn resolve_transfer_in(
+        &mut self,
+        pool_id: U64,
+        token_account_id: AccountId,
+        amount: U128
+    ) {
+        assert_self();
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.unlock();
+        if is_promise_success(0) {
+            self.pools.insert(&pool_id.into(), &pool);
+            return;
+        }
+
+        pool.rollback_transfer_in(&token_account_id, amount.into());
+        self.pools.insert(&pool_id.into(), &pool);
+        self.decrease_custody(&token_account_id, amount.into());
+
+        log_transfer_failed(pool_id, &token_account_id, &pool.get_controller(), amount);
+    }
+
+    /**
+     * @notice credits a payout that failed to `receiver_id`, the tokens never left the factory and can be claimed
+     */
+    pub fn resolve_transfer_out(
+        &mut self,
+        pool_id: U64,
+        token_account_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128
+    ) {
+        assert_self();
+        if is_promise_success(0) {
+            self.decrease_custody(&token_account_id, amount.into());
+            return;
+        }
+
+        self.credit_claimable(&receiver_id, &token_account_id, amount.into());
+
+        log_transfer_failed(pool_id, &token_account_id, &receiver_id, amount);
+    }
+
+    /**
+     * @notice mints the pool tokens of a join once every token pull succeeded,
+     * rolls back the join otherwise and refunds the pulls that did succeed
+     * @dev unlocks the pool either way
+     */
+    pub fn resolve_join(
+        &mut self,
+        pool_id: U64,
+        sender: AccountId,
+        tokens: Vec<AccountId>,
+        amounts_in: Vec<U128>,
+        pool_amount_out: U128
+    ) {
+        assert_self();
+        let results: Vec<bool> = (0..env::promise_results_count()).map(is_promise_success).collect();
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.unlock();
+        if results.iter().all(|success| *success) {
+            pool.mint_joined(&sender, pool_amount_out.into());
+            self.pools.insert(&pool_id.into(), &pool);
+            return;
+        }
+
+        for (i, token) in tokens.iter().enumerate() {
+            let amount_in = u128::from(amounts_in[i]);
+            pool.rollback_transfer_in(token, amount_in);
+            if results[i] {
+                self.refund_underlying(token, &sender, amount_in);
+            } else {
+                self.decrease_custody(token, amount_in);
+                log_transfer_failed(pool_id, token, &sender, amounts_in[i]);
+            }
+        }
+        self.pools.insert(&pool_id.into(), &pool);
+    }
+
+    /**
+     * @notice finalizes an outcome pool once every token pull succeeded, unbinds every token otherwise
+     * and refunds the pulls that did succeed
+     * @dev unlocks the pool either way, it's only indexed once it's finalized.
+     *      The controller can't change while the pool is locked, so `sender` still controls it
+     */
+    pub fn resolve_outcome_pool(
+        &mut self,
+        pool_id: U64,
+        sender: AccountId,
+        tokens: Vec<AccountId>
+    ) {
+        assert_self();
+        let results: Vec<bool> = (0..env::promise_results_count()).map(is_promise_success).collect();
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.unlock();
+        if results.iter().all(|success| *success) {
+            pool.finalize(&sender);
+            self.index_pool(pool_id.into(), &pool);
+            self.pools.insert(&pool_id.into(), &pool);
+            return;
+        }
+
+        for (i, token) in tokens.iter().enumerate() {
+            let balance = pool.unbind(&sender, token);
+            if results[i] {
+                self.refund_underlying(token, &sender, balance);
+            } else {
+                self.decrease_custody(token, balance);
+                log_transfer_failed(pool_id, token, &sender, balance.into());
+            }
+        }
+        self.pools.insert(&pool_id.into(), &pool);
+    }
+
+    /**
+     * @notice pays out `token_out` and accrues the protocol fee once `token_in` has been received, rolls back both balances otherwise
+     * @dev unlocks the pool either way, a failed payout is credited to `sender` to be claimed
+     */
+    pub fn resolve_swap(
+        &mut self,
+        pool_id: U64,
+        sender: AccountId,
+        token_in: AccountId,
+        token_amount_in: U128,
+        token_out: AccountId,
+        token_amount_out: U128,
+        protocol_fee_amount: U128
+    ) {
+        assert_self();
+        let protocol_fee_amount = u128::from(protocol_fee_amount);
+        let mut pool = self.pools.get(&pool_id.into()).expect("ERR_NO_POOL");
+        pool.unlock();
+        if is_promise_success(0) {
+            self.pools.insert(&pool_id.into(), &pool);
+            self.accrue_protocol_fee(&token_in, protocol_fee_amount);
+            self.push_underlying(pool_id, &token_out, &sender, token_amount_out.into());
+            return;
+        }
+
+        // The withheld protocol fee never reached the pool's balance
+        pool.rollback_transfer_in(&token_in, u128::from(token_amount_in) - protocol_fee_amount);
+        pool.rollback_transfer_out(&token_out, token_amount_out.into());
+        self.pools.insert(&pool_id.into(), &pool);
+        self.decrease_custody(&token_in, token_amount_in.into());
+
+        log_transfer_failed(pool_id, &token_in, &sender, token_amount_in);
+    }
+
+    /**
+     * @notice pays out every route's output once the input was received, rolls back every step otherwise
+     */
+    pub fn resolve_batch_swap(
+        &mut self,
+        sender: AccountId,
+        token_in: AccountId,
+        token_amount_in: U128,
+        token_out: AccountId,
+        swaps: Vec<ExecutedSwap>,
+        payouts: Vec<(U64, U128)>
+    ) {
+        assert_self();
+        if is_promise_success(0) {
+            for swap in &swaps {
+                let mut pool = self.pools.get(&swap.pool_id.into()).expect("ERR_NO_POOL");
+                pool.unlock();
+                self.pools.insert(&swap.pool_id.into(), &pool);
+                self.accrue_protocol_fee(&swap.token_in, swap.protocol_fee_amount.into());
+            }
+            for (pool_id, amount) in payouts {
+                self.push_underlying(pool_id, &token_out, &sender, amount.into());
+            }
+            return;
+        }
+
+        // Roll back in reverse order of execution so every pool returns to its previous state
+        for swap in swaps.iter().rev() {
+            let mut pool = self.pools.get(&swap.pool_id.into()).expect("ERR_NO_POOL");
+            pool.rollback_transfer_in(&swap.token_in, u128::from(swap.token_amount_in) - u128::from(swap.protocol_fee_amount));
+            pool.rollback_transfer_out(&swap.token_out, swap.token_amount_out.into());
+            pool.unlock();
+            self.pools.insert(&swap.pool_id.into(), &pool);
+        }
+        self.decrease_custody(&token_in, token_amount_in.into());
+
+        let first_pool_id = swaps.iter().find(|swap| swap.token_in == token_in).expect("ERR_NO_SWAPS").pool_id;
+        log_transfer_failed(first_pool_id, &token_in, &sender, token_amount_in);
+    }
+
+    /**
+     * @notice calls the flash swap receiver once it got `token_out` and pulls the repayment afterwards,
+     * rolls back the swap and unlocks the pool if the transfer failed
+     */
+    pub fn resolve_flash_swap_transfer(
+        &mut self,
+        sender: AccountId,
+        receiver_id: AccountId,
+        swap: ExecutedSwap,
+        msg: String
+    ) {
+        assert_self();
+        let token_amount_in = u128::from(swap.token_amount_in);
+        if !is_promise_success(0) {
+            let mut pool = self.pools.get(&swap.pool_id.into()).expect("ERR_NO_POOL");
+            pool.rollback_transfer_in(&swap.token_in, token_amount_in - u128::from(swap.protocol_fee_amount));
+            pool.rollback_transfer_out(&swap.token_out, swap.token_amount_out.into());
+            pool.unlock();
+            self.pools.insert(&swap.pool_id.into(), &pool);
+
+            log_transfer_failed(swap.pool_id, &swap.token_out, &receiver_id, swap.token_amount_out);
+            return;
+        }
+
+        // `token_out` left custody, the repayment is part of it as soon as it's requested
+        self.decrease_custody(&swap.token_out, swap.token_amount_out.into());
+        self.increase_custody(&swap.token_in, token_amount_in);
+
+        ext_flash_swap_receiver::on_flash_swap(
+            swap.pool_id,
+            sender,
+            swap.token_in.to_string(),
+            swap.token_amount_in,
+            swap.token_out.to_string(),
+            swap.token_amount_out,
+            msg,
+            &receiver_id,
+            0,
+            GAS_FOR_FLASH_SWAP_CALLBACK
+        )
+        .then(transfer_from(&swap.token_in, &receiver_id, token_amount_in))
+        .then(ext_self::resolve_flash_swap(
+            receiver_id.to_string(),
+            swap,
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_TRANSFER
+        ));
+    }
+
+    /**
+     * @notice accrues the protocol fee once the flash swap was repaid, takes the missing repayment
+     * out of the pool's balance otherwise so it only holds what's in custody
+     * @dev unlocks the pool either way
+     */
+    pub fn resolve_flash_swap(
+        &mut self,
+        receiver_id: AccountId,
+        swap: ExecutedSwap
+    ) {
+        assert_self();
+        let token_amount_in = u128::from(swap.token_amount_in);
+        let protocol_fee_amount = u128::from(swap.protocol_fee_amount);
+        let mut pool = self.pools.get(&swap.pool_id.into()).expect("ERR_NO_POOL");
+        pool.unlock();
+        if is_promise_success(0) {
+            self.pools.insert(&swap.pool_id.into(), &pool);
+            self.accrue_protocol_fee(&swap.token_in, protocol_fee_amount);
+            return;
+        }
+
+        pool.rollback_transfer_in(&swap.token_in, token_amount_in - protocol_fee_amount);
+        self.pools.insert(&swap.pool_id.into(), &pool);
+        self.decrease_custody(&swap.token_in, token_amount_in);
+
+        log_transfer_failed(swap.pool_id, &swap.token_in, &receiver_id, swap.token_amount_in);
+    }
+
+    /**
+     * @notice releases collected protocol fees from custody, re-accrues them if the transfer to the owner failed
+     */
+    pub fn resolve_collect_protocol_fee(
+        &mut self,
+        token_account_id: AccountId,
+        amount: U128
+    ) {
+        assert_self();
+        if is_promise_success(0) {
+            self.decrease_custody(&token_account_id, amount.into());
+        } else {
+            self.accrue_protocol_fee(&token_account_id, amount.into());
+        }
+    }
+
+    /**
+     * @notice releases refunded tokens from custody once they've left the factory, credits them to `receiver_id` otherwise
+     */
+    pub fn resolve_refund(
+        &mut self,
+        token_account_id: AccountId,
+        receiver_id: AccountId,
+        amount: U128
+    ) {
+        assert_self();
+        if is_promise_success(0) {
+            self.decrease_custody(&token_account_id, amount.into());
+        } else {
+            self.credit_claimable(&receiver_id, &token_account_id, amount.into());
+        }
+    }
+}
+
+/*** INTERNAL TRANSFER HELPERS ***/
+impl PoolFactory {
+    fn check_not_paused(&self) -> PoolResult<()> {
+        if self.paused {
+            Err(PoolError::Paused)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn assert_not_paused(&self) {
+        assert!(!self.paused, "ERR_PAUSED");
+    }
+
+    fn increase_custody(&mut self, token_account_id: &AccountId, amount: u128) {
+        let custody = self.custody.get(token_account_id).unwrap_or(0);
+        self.custody.insert(token_account_id, &(custody + amount));
+    }
+
+    fn decrease_custody(&mut self, token_account_id: &AccountId, amount: u128) {
+        let custody = self.custody.get(token_account_id).unwrap_or(0);
+        self.custody.insert(token_account_id, &(custody - amount));
+    }
+
+    // Failed payouts stay in custody until they're claimed
+    fn credit_claimable(&mut self, account_id: &AccountId, token_account_id: &AccountId, amount: u128) {
+        let key = (account_id.to_string(), token_account_id.to_string());
+        let claimable = self.claimable.get(&key).unwrap_or(0);
+        self.claimable.insert(&key, &(claimable + amount));
+    }
+
+    // Protocol fees stay in custody until they're collected
+    fn accrue_protocol_fee(&mut self, token_account_id: &AccountId, amount: u128) {
+        if amount == 0 {
+            return;
+        }
+        let accrued = self.protocol_fees.get(token_account_id).unwrap_or(0);
+        self.protocol_fees.insert(token_account_id, &(accrued + amount));
+    }
+
+    /**
+     * @notice adds `pool_id` to the index of `token_account_id` and of every pair it forms with the pool's other tokens
+     */
+    fn index_token(&mut self, pool_id: u64, pool: &Pool, token_account_id: &AccountId) {
+        let mut token_pools = self.token_pools
+            .get(token_account_id)
+            .unwrap_or_else(|| UnorderedSet::new(format!("tps:{}:", token_account_id).as_bytes().to_vec()));
+        token_pools.insert(&pool_id);
+        self.token_pools.insert(token_account_id, &token_pools);
+
+        for other in pool.get_current_tokens().iter().filter(|token| *token != token_account_id) {
+            let key = pair_key(token_account_id, other);
+            let mut pair_pools = self.pair_pools
+                .get(&key)
+                .unwrap_or_else(|| UnorderedSet::new(format!("pps:{}:{}:", key.0, key.1).as_bytes().to_vec()));
+            pair_pools.insert(&pool_id);
+            self.pair_pools.insert(&key, &pair_pools);
+        }
+    }
+
+    /**
+     * @notice indexes `pool_id` under every token it's bound to once its tokens can't change anymore
+     * @dev bind and unbind already keep the index up to date, this covers pools that were bound before the index existed
+     */
+    fn index_pool(&mut self, pool_id: u64, pool: &Pool) {
+        for token_account_id in pool.get_current_tokens() {
+            self.index_token(pool_id, pool, &token_account_id);
+        }
+    }
+
+    /**
+     * @notice removes `pool_id` from the index of `token_account_id` and of every pair it formed with the remaining tokens
+     */
+    fn unindex_token(&mut self, pool_id: u64, pool: &Pool, token_account_id: &AccountId) {
+        if let Some(mut token_pools) = self.token_pools.get(token_account_id) {
+            token_pools.remove(&pool_id);
+            self.token_pools.insert(token_account_id, &token_pools);
+        }
+
+        for other in pool.get_current_tokens() {
+            let key = pair_key(token_account_id, &other);
+            if let Some(mut pair_pools) = self.pair_pools.get(&key) {
+                pair_pools.remove(&pool_id);
+                self.pair_pools.insert(&key, &pair_pools);
+            }
+        }
+    }
+
+    fn pull_underlying(
+        &mut self,
+        pool_id: U64,
+        token_account_id: &AccountId,
+        sender: &AccountId,
+        amount: u128
+    ) -> Promise {
+        let pull = transfer_from(token_account_id, sender, amount);
+        self.then_resolve_transfer_in(pull, pool_id, token_account_id, amount)
+    }
+
+    fn push_underlying(
+        &self,
+        pool_id: U64,
+        token_account_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: u128
+    ) -> Promise {
+        ext_fungible_token::transfer(
+            receiver_id.to_string(),
+            U128(amount),
+            token_account_id,
+            0,
+            GAS_FOR_FT_TRANSFER
+        ).then(ext_self::resolve_transfer_out(
+            pool_id,
+            token_account_id.to_string(),
+            receiver_id.to_string(),
+            U128(amount),
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_TRANSFER
+        ))
+    }
+
+    fn refund_underlying(
+        &self,
+        token_account_id: &AccountId,
+        receiver_id: &AccountId,
+        amount: u128
+    ) -> Promise {
+        ext_fungible_token::transfer(
+            receiver_id.to_string(),
+            U128(amount),
+            token_account_id,
+            0,
+            GAS_FOR_FT_TRANSFER
+        ).then(ext_self::resolve_refund(
+            token_account_id.to_string(),
+            receiver_id.to_string(),
+            U128(amount),
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_TRANSFER
+        ))
+    }
+
+    fn pull_for_join(
+        &mut self,
+        pool_id: U64,
+        sender: &AccountId,
+        tokens: Vec<AccountId>,
+        amounts_in: Vec<u128>,
+        pool_amount_out: u128
+    ) -> Promise {
+        let mut pulls: Option<Promise> = None;
+        for (token, amount_in) in tokens.iter().zip(amounts_in.iter()) {
+            let pull = transfer_from(token, sender, *amount_in);
+            pulls = Some(match pulls {
+                Some(promise) => promise.and(pull),
+                None => pull
+            });
+        }
+
+        self.then_resolve_join(pulls.expect("ERR_NO_TOKENS"), pool_id, sender, tokens, amounts_in, pool_amount_out)
+    }
+
+    /**
+     * @return every route from `token_in` to `token_out` of at most `max_hops` steps that doesn't visit a pool or token twice
+     * @dev only the pools that are within `max_hops` of `token_in` are loaded, they're found through the token index
+     */
+    fn find_routes(
+        &self,
+        token_in: &AccountId,
+        token_out: &AccountId,
+        max_hops: u64
+    ) -> Vec<Vec<SwapStep>> {
+        let mut pools: Vec<(u64, Vec<AccountId>)> = vec![];
+        let mut visited = vec![token_in.to_string()];
+        let mut frontier = vec![token_in.to_string()];
+        for _ in 0..max_hops {
+            let mut next_frontier = vec![];
+            for token in &frontier {
+                let pool_ids = match self.token_pools.get(token) {
+                    Some(pool_ids) => pool_ids,
+                    None => continue
+                };
+                for pool_id in pool_ids.iter() {
+                    if pools.iter().any(|(id, _)| *id == pool_id) {
+                        continue;
+                    }
+                    let pool = self.pools.get(&pool_id).expect("ERR_NO_POOL");
+                    if !pool.is_public_swap() {
+                        continue;
+                    }
+
+                    let tokens = pool.get_current_tokens();
+                    for next in &tokens {
+                        if !visited.contains(next) {
+                            visited.push(next.to_string());
+                            next_frontier.push(next.to_string());
+                        }
+                    }
+                    pools.push((pool_id, tokens));
+                }
+            }
+            frontier = next_frontier;
+        }
+
+        let mut routes = vec![];
+        let mut path = vec![];
+        extend_routes(&pools, token_in, token_out, max_hops, &mut path, &mut routes);
+        routes
+    }
+
+    /**
+     * @return the amount of the route's last token a swap of `token_amount_in` pays out, `None` if a step can't take it
+     */
+    fn quote_route(
+        &self,
+        route: &[SwapStep],
+        token_amount_in: u128
+    ) -> Option<u128> {
+        route.iter().try_fold(token_amount_in, |token_amount, step| {
+            let pool = self.pools.get(&step.pool_id.into())?;
+            pool.quote_exact_amount_in(&step.token_in, token_amount, &step.token_out)
+        })
+    }
+
+    fn internal_swap_exact_in(
+        &mut self,
+        sender: &AccountId,
+        step: &SwapStep,
+        token_amount_in: u128
+    ) -> ExecutedSwap {
+        self.assert_not_paused();
+        let mut pool = self.pools.get(&step.pool_id.into()).expect("ERR_NO_POOL");
+        // Slippage is only checked against the total of the route
+        let (token_amount_out, _) = pool.swap_exact_amount_in(
+            sender,
+            &step.token_in,
+            token_amount_in,
+            &step.token_out,
+            0,
+            u128::MAX
+        );
+        let protocol_fee_amount = pool.withhold_protocol_fee(&step.token_in, token_amount_in, self.protocol_fee);
+        self.pools.insert(&step.pool_id.into(), &pool);
+
+        ExecutedSwap {
+            pool_id: step.pool_id,
+            token_in: step.token_in.to_string(),
+            token_out: step.token_out.to_string(),
+            token_amount_in: token_amount_in.into(),
+            token_amount_out: token_amount_out.into(),
+            protocol_fee_amount: protocol_fee_amount.into()
+        }
+    }
+
+    fn internal_swap_exact_out(
+        &mut self,
+        sender: &AccountId,
+        step: &SwapStep,
+        token_amount_out: u128
+    ) -> ExecutedSwap {
+        self.assert_not_paused();
+        let mut pool = self.pools.get(&step.pool_id.into()).expect("ERR_NO_POOL");
+        let (token_amount_in, _) = pool.swap_exact_amount_out(
+            sender,
+            &step.token_in,
+            u128::MAX,
+            &step.token_out,
+            token_amount_out,
+            u128::MAX
+        );
+        let protocol_fee_amount = pool.withhold_protocol_fee(&step.token_in, token_amount_in, self.protocol_fee);
+        self.pools.insert(&step.pool_id.into(), &pool);
+
+        ExecutedSwap {
+            pool_id: step.pool_id,
+            token_in: step.token_in.to_string(),
+            token_out: step.token_out.to_string(),
+            token_amount_in: token_amount_in.into(),
+            token_amount_out: token_amount_out.into(),
+            protocol_fee_amount: protocol_fee_amount.into()
+        }
+    }
+
+    fn pull_for_batch_swap(
+        &mut self,
+        sender: &AccountId,
+        token_in: &AccountId,
+        token_amount_in: u128,
+        token_out: &AccountId,
+        swaps: Vec<ExecutedSwap>,
+        payouts: Vec<(U64, U128)>
+    ) -> Promise {
+        self.increase_custody(token_in, token_amount_in);
+
+        // Leave enough gas in the callback to pay out every route
+        let gas_for_resolve = GAS_FOR_RESOLVE_TRANSFER + (GAS_FOR_FT_TRANSFER + GAS_FOR_RESOLVE_TRANSFER) * payouts.len() as u64;
+
+        transfer_from(token_in, sender, token_amount_in).then(ext_self::resolve_batch_swap(
+            sender.to_string(),
+            token_in.to_string(),
+            U128(token_amount_in),
+            token_out.to_string(),
+            swaps,
+            payouts,
+            &env::current_account_id(),
+            0,
+            gas_for_resolve
+        ))
+    }
+
+    fn pull_for_swap(
+        &mut self,
+        pool_id: U64,
+        sender: &AccountId,
+        token_in: &AccountId,
+        token_amount_in: u128,
+        token_out: &AccountId,
+        token_amount_out: u128,
+        protocol_fee_amount: u128
+    ) -> Promise {
+        let pull = transfer_from(token_in, sender, token_amount_in);
+        self.then_resolve_swap(pull, pool_id, sender, token_in, token_amount_in, token_out, token_amount_out, protocol_fee_amount)
+    }
+
+    /*
+     * Pulled tokens are added to custody as soon as they're requested so a gulp can never
+     * count them as surplus, the resolve callbacks remove them again if the pull failed
+     */
+
+    fn then_resolve_transfer_in(
+        &mut self,
+        pull: Promise,
+        pool_id: U64,
+        token_account_id: &AccountId,
+        amount: u128
+    ) -> Promise {
+        self.increase_custody(token_account_id, amount);
+
+        pull.then(ext_self::resolve_transfer_in(
+            pool_id,
+            token_account_id.to_string(),
+            U128(amount),
+            &env::current_account_id(),
+            0,
+            GAS_FOR_RESOLVE_TRANSFER
+        ))
+    }
+
+    fn then_resolve_join(
+        &mut self,
+        pulls: Promise,
+        pool_id: U64,
+        sender: &AccountId,
+        tokens: Vec<AccountId>,
+        amounts_in: Vec<u128>,
+        pool_amount_out: u128
+    ) -> Promise {
+        for (token, amount_in) in tokens.iter().zip(amounts_in.iter()) {
+            self.increase_custody(token, *amount_in);
+        }
+
+        // Leave enough gas in the callback to refund every token if the join has to be rolled back
+        let gas_for_resolve = GAS_FOR_RESOLVE_TRANSFER + (GAS_FOR_FT_TRANSFER + GAS_FOR_RESOLVE_TRANSFER) * tokens.len() as u64;
+
+        pulls.then(ext_self::resolve_join(
+            pool_id,
+            sender.to_string(),
+            tokens,
+            amounts_in.into_iter().map(U128).collect(),
+            U128(pool_amount_out),
+            &env::current_account_id(),
+            0,
+            gas_for_resolve
+        ))
+    }
+
+    fn then_resolve_swap(
+        &mut self,
+        pull: Promise,
+        pool_id: U64,
+        sender: &AccountId,
+        token_in: &AccountId,
+        token_amount_in: u128,
+        token_out: &AccountId,
+        token_amount_out: u128,
+        protocol_fee_amount: u128
+    ) -> Promise {
+        self.increase_custody(token_in, token_amount_in);
+
+        pull.then(ext_self::resolve_swap(
+            pool_id,
+            sender.to_string(),
+            token_in.to_string(),
+            U128(token_amount_in),
+            token_out.to_string(),
+            U128(token_amount_out),
+            U128(protocol_fee_amount),
+            &env::current_account_id(),
+            0,
+            GAS_FOR_FT_TRANSFER + 2 * GAS_FOR_RESOLVE_TRANSFER
+        ))
+    }
+}
+
+fn extend_routes(
+    pools: &[(u64, Vec<AccountId>)],
+    token: &AccountId,
+    token_out: &AccountId,
+    max_hops: u64,
+    path: &mut Vec<SwapStep>,
+    routes: &mut Vec<Vec<SwapStep>>
+) {
+    for (pool_id, tokens) in pools {
+        if !tokens.contains(token) || path.iter().any(|step| u64::from(step.pool_id) == *pool_id) {
+            continue;
+        }
+
+        for next in tokens {
+            if next == token || path.iter().any(|step| &step.token_in == next) {
+                continue;
+            }
+
+            path.push(SwapStep {
+                pool_id: U64(*pool_id),
+                token_in: token.to_string(),
+                token_out: next.to_string()
+            });
+            if next == token_out {
+                routes.push(path.clone());
+            } else if (path.len() as u64) < max_hops {
+                extend_routes(pools, next, token_out, max_hops, path, routes);
+            }
+            path.pop();
+        }
+    }
+}
+
+fn pair_key(token_a: &AccountId, token_b: &AccountId) -> (AccountId, AccountId) {
+    if token_a < token_b {
+        (token_a.to_string(), token_b.to_string())
+    } else {
+        (token_b.to_string(), token_a.to_string())
+    }
+}
+
+fn paginate(pools: &UnorderedSet<u64>, from_index: u64, limit: u64) -> Vec<U64> {
+    let pools = pools.as_vector();
+    let to_index = from_index.saturating_add(limit).min(pools.len());
+    (from_index..to_index)
+        .map(|index| U64(pools.get(index).expect("ERR_NO_POOL")))
+        .collect()
+}
+
+fn routes_overlap(a: &[SwapStep], b: &[SwapStep]) -> bool {
+    a.iter().any(|step_a| b.iter().any(|step_b| step_a.pool_id == step_b.pool_id))
+}
+
+/**
+ * @panics if `route` is empty or a step doesn't start with the token the previous step ended with
+ */
+fn assert_valid_route(route: &[SwapStep]) {
+    assert!(!route.is_empty(), "ERR_EMPTY_ROUTE");
+    for steps in route.windows(2) {
+        assert_eq!(steps[0].token_out, steps[1].token_in, "ERR_INVALID_ROUTE");
+    }
+}
+
+fn transfer_from(
+    token_account_id: &AccountId,
+    owner_id: &AccountId,
+    amount: u128
+) -> Promise {
+    ext_fungible_token::transfer_from(
+        owner_id.to_string(),
+        env::current_account_id(),
+        U128(amount),
+        token_account_id,
+        0,
+        GAS_FOR_FT_TRANSFER
+    )
+}
+
+fn withdraw_from_vault(
+    vault_id: VaultId,
+    token_account_id: &AccountId,
+    amount: u128
+) -> Promise {
+    ext_fungible_token::withdraw_from_vault(
+        vault_id,
+        env::current_account_id(),
+        U128(amount),
+        token_account_id,
+        0,
+        GAS_FOR_FT_TRANSFER
+    )
+}
+
+fn log_transfer_failed(
+    pool_id: U64,
+    token_account_id: &AccountId,
+    account_id: &AccountId,
+    amount: U128
+) {
+    logger::log(Event::TransferFailed(TransferFailedEvent {
+        pool_id,
+        token: token_account_id.to_string(),
+        account_id: account_id.to_string(),
+        amount
+    }));
+}
+
+fn assert_self() {
+    assert_eq!(env::current_account_id(), env::predecessor_account_id(), "ERR_PRIVATE_METHOD");
+}
+
+fn assert_one_yocto() {
+    assert_eq!(env::attached_deposit(), 1, "ERR_ONE_YOCTO");
+}
+
+fn is_promise_success(index: u64) -> bool {
+    match env::promise_result(index) {
+        PromiseResult::Successful(_) => true,
+        _ => false
+    }
+}
@@ -1,6 +1,8 @@
 use near_sdk::{
+    env,
     AccountId, 
     VMContext, 
+    VmPromiseResult,
     testing_env, 
     MockedBlockchain, 
     json_types::{
@@ -68,10 +70,87 @@ fn get_context(
     }
 }
 
+// Sets up the context of a callback whose promises resolved with `promise_results`, keeping the contract's storage
+fn testing_env_with_promise_results(context: VMContext, promise_results: Vec<VmPromiseResult>) {
+    let storage = env::take_blockchain_interface()
+        .unwrap()
+        .as_mut_mocked_blockchain()
+        .unwrap()
+        .take_storage();
+
+    env::set_blockchain_interface(Box::new(MockedBlockchain::new(
+        context,
+        Default::default(),
+        Default::default(),
+        promise_results,
+        storage,
+        Default::default()
+    )));
+}
+
+fn promise_results(successes: &[bool]) -> Vec<VmPromiseResult> {
+    successes.iter().map(|success| match success {
+        true => VmPromiseResult::Successful(vec![]),
+        false => VmPromiseResult::Failed
+    }).collect()
+}
+
+// Binds `token_account_id` and stands in for the callback of its pull, the predecessor stays the same afterwards
+fn bind_pool(
+    contract: &mut PoolFactory,
+    pool_id: U64,
+    token_account_id: &AccountId,
+    denorm: U128,
+    balance: U128
+) {
+    let predecessor = env::predecessor_account_id();
+    let block_timestamp = env::block_timestamp();
+    contract.bind_pool(pool_id, token_account_id, denorm, balance);
+
+    testing_env_with_promise_results(get_context(alice(), block_timestamp), promise_results(&[true]));
+    contract.resolve_transfer_in(pool_id, token_account_id.to_string(), balance);
+    testing_env!(get_context(predecessor, block_timestamp));
+}
+
+// Stands in for the callback of a join whose token pulls all succeeded, `sender` is the predecessor afterwards
+fn resolve_join(
+    contract: &mut PoolFactory,
+    pool_id: U64,
+    sender: AccountId,
+    amounts_in: Vec<(AccountId, u128)>,
+    pool_amount_out: u128
+) {
+    testing_env_with_promise_results(get_context(alice(), 0), promise_results(&vec![true; amounts_in.len()]));
+    let (tokens, amounts_in) = amounts_in.into_iter().map(|(token, amount)| (token, U128(amount))).unzip();
+    contract.resolve_join(pool_id, sender.to_string(), tokens, amounts_in, U128(pool_amount_out));
+    testing_env!(get_context(sender, 0));
+}
+
 mod factory_tests;
 mod pool_initiation_test;
 mod pricing_tests;
 mod dynamic_pool_tests;
 mod swap_tests;
 mod init_tests;
-mod math_tests;
\ No newline at end of file
+mod math_tests;
+mod simulation_tests;
+mod vault_receiver_tests;
+mod logger_tests;
+mod pool_token_tests;
+mod gulp_tests;
+mod protocol_fee_tests;
+mod weight_update_tests;
+mod batch_swap_tests;
+mod routing_tests;
+mod pool_index_tests;
+mod pool_info_tests;
+mod preview_tests;
+mod oracle_tests;
+mod flash_swap_tests;
+mod lock_tests;
+mod pause_tests;
+mod controller_tests;
+mod swap_fee_tests;
+mod dynamic_fee_tests;
+mod exit_fee_tests;
+mod outcome_pool_tests;
\ No newline at end of file
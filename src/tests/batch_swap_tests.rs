@@ -0,0 +1,103 @@
+use super::*;
+use crate::pool_factory::SwapStep;
+
+fn create_pool(contract: &mut PoolFactory, token_in: AccountId, token_out: AccountId) -> U64 {
+    let pool_id = contract.new_pool(swap_fee());
+
+    for token in &[token_in, token_out] {
+        bind_pool(contract, 
+            pool_id,
+            token,
+            U128(to_token_denom(10)),
+            U128(to_token_denom(100))
+        );
+    }
+    contract.finalize_pool(pool_id);
+
+    pool_id
+}
+
+// Pool 1 trades token a for b, pool 2 trades token b for c
+fn create_pools() -> PoolFactory {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    create_pool(&mut contract, token_a(), token_b());
+    create_pool(&mut contract, token_b(), token_c());
+
+    testing_env!(get_context(bob(), 0));
+    contract
+}
+
+fn route() -> Vec<SwapStep> {
+    vec![
+        SwapStep { pool_id: U64(1), token_in: token_a(), token_out: token_b() },
+        SwapStep { pool_id: U64(2), token_in: token_b(), token_out: token_c() }
+    ]
+}
+
+#[test]
+fn test_batch_swap_exact_in() {
+    // Same result as swapping both legs separately, created first since every contract resets the mocked blockchain
+    let mut expected_contract = create_pools();
+    let (amount_b, _) = expected_contract.swap_exact_amount_in(U64(1), &token_a(), U128(to_token_denom(10)), &token_b(), U128(0), U128(to_token_denom(1000)));
+    let (amount_c, _) = expected_contract.swap_exact_amount_in(U64(2), &token_b(), amount_b, &token_c(), U128(0), U128(to_token_denom(1000)));
+
+    let mut contract = create_pools();
+    let amount_out: u128 = contract.batch_swap_exact_in(route(), U128(to_token_denom(10)), U128(0)).into();
+
+    assert_eq!(amount_out, u128::from(amount_c));
+    assert_eq!(contract.get_pool_balance(U64(1), &token_a()), U128(to_token_denom(110)));
+    assert_eq!(contract.get_pool_balance(U64(1), &token_b()), U128(to_token_denom(100) - u128::from(amount_b)));
+    assert_eq!(contract.get_pool_balance(U64(2), &token_b()), U128(to_token_denom(100) + u128::from(amount_b)));
+    assert_eq!(contract.get_pool_balance(U64(2), &token_c()), U128(to_token_denom(100) - amount_out));
+
+    // Only the first token is pulled into custody, the intermediate token never leaves the factory
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(110)));
+    assert_eq!(contract.get_custody_balance(&token_b()), U128(to_token_denom(200)));
+}
+
+#[test]
+fn test_batch_swap_exact_out() {
+    let mut expected_contract = create_pools();
+    let (amount_b, _) = expected_contract.swap_exact_amount_out(U64(2), &token_b(), U128(to_token_denom(1000)), &token_c(), U128(to_token_denom(5)), U128(to_token_denom(1000)));
+    let (amount_a, _) = expected_contract.swap_exact_amount_out(U64(1), &token_a(), U128(to_token_denom(1000)), &token_b(), amount_b, U128(to_token_denom(1000)));
+
+    let mut contract = create_pools();
+    let amount_in: u128 = contract.batch_swap_exact_out(route(), U128(to_token_denom(5)), U128(to_token_denom(1000))).into();
+
+    assert_eq!(amount_in, u128::from(amount_a));
+    assert_eq!(contract.get_pool_balance(U64(1), &token_a()), U128(to_token_denom(100) + amount_in));
+    assert_eq!(contract.get_pool_balance(U64(2), &token_c()), U128(to_token_denom(95)));
+}
+
+#[test]
+#[should_panic(expected = "ERR_LIMIT_OUT")]
+fn test_batch_swap_exact_in_limit_out() {
+    let mut contract = create_pools();
+    contract.batch_swap_exact_in(route(), U128(to_token_denom(10)), U128(to_token_denom(10)));
+}
+
+#[test]
+#[should_panic(expected = "ERR_LIMIT_IN")]
+fn test_batch_swap_exact_out_limit_in() {
+    let mut contract = create_pools();
+    contract.batch_swap_exact_out(route(), U128(to_token_denom(5)), U128(to_token_denom(5)));
+}
+
+#[test]
+#[should_panic(expected = "ERR_INVALID_ROUTE")]
+fn test_batch_swap_invalid_route() {
+    let mut contract = create_pools();
+    let mut route = route();
+    route.reverse();
+    contract.batch_swap_exact_in(route, U128(to_token_denom(10)), U128(0));
+}
+
+#[test]
+#[should_panic(expected = "ERR_EMPTY_ROUTE")]
+fn test_batch_swap_empty_route() {
+    let mut contract = create_pools();
+    contract.batch_swap_exact_in(vec![], U128(to_token_denom(10)), U128(0));
+}
@@ -0,0 +1,157 @@
+use super::*;
+use crate::logger::{
+    self,
+    Event,
+    ControllerEvent,
+    EVENT_VERSION
+};
+
+fn create_pool(finalize: bool) -> (PoolFactory, U64) {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    let pool_id = contract.new_pool(swap_fee());
+
+    for token in &[token_a(), token_b()] {
+        bind_pool(&mut contract, 
+            pool_id,
+            token,
+            U128(to_token_denom(10)),
+            U128(to_token_denom(100))
+        );
+    }
+    if finalize {
+        contract.finalize_pool(pool_id);
+    }
+
+    (contract, pool_id)
+}
+
+fn join(contract: &mut PoolFactory, pool_id: U64) {
+    let pool_amount_out = contract.join_swap_extern_amount_in(pool_id, &token_a(), U128(to_token_denom(1)), U128(0));
+    resolve_join(contract, pool_id, bob(), vec![(token_a(), to_token_denom(1))], pool_amount_out.into());
+}
+
+#[test]
+fn test_set_controller_is_two_step() {
+    let (mut contract, pool_id) = create_pool(false);
+    contract.set_controller(pool_id, bob());
+
+    assert_eq!(contract.get_pool_controller(pool_id), alice());
+    assert_eq!(contract.get_pool_pending_controller(pool_id), Some(bob()));
+
+    testing_env!(get_context(bob(), 0));
+    contract.accept_controller(pool_id);
+
+    assert_eq!(contract.get_pool_controller(pool_id), bob());
+    assert_eq!(contract.get_pool_pending_controller(pool_id), None);
+
+    contract.set_public_swap(pool_id, true);
+    assert!(contract.pool_is_public_swap(pool_id));
+}
+
+#[test]
+#[should_panic(expected = "ERR_NO_CONTROLLER")]
+fn test_previous_controller_loses_rights() {
+    let (mut contract, pool_id) = create_pool(false);
+    contract.set_controller(pool_id, bob());
+    testing_env!(get_context(bob(), 0));
+    contract.accept_controller(pool_id);
+
+    testing_env!(get_context(alice(), 0));
+    contract.set_public_swap(pool_id, true);
+}
+
+#[test]
+#[should_panic(expected = "ERR_NO_CONTROLLER")]
+fn test_set_controller_not_controller() {
+    let (mut contract, pool_id) = create_pool(false);
+    testing_env!(get_context(bob(), 0));
+    contract.set_controller(pool_id, bob());
+}
+
+#[test]
+#[should_panic(expected = "ERR_NOT_PENDING_CONTROLLER")]
+fn test_accept_controller_not_pending() {
+    let (mut contract, pool_id) = create_pool(false);
+    contract.set_controller(pool_id, bob());
+
+    testing_env!(get_context(carol(), 0));
+    contract.accept_controller(pool_id);
+}
+
+#[test]
+#[should_panic(expected = "ERR_REENTRY")]
+fn test_accept_controller_while_locked() {
+    let (mut contract, pool_id) = create_pool(false);
+    contract.set_controller(pool_id, bob());
+    contract.rebind_pool(pool_id, &token_a(), U128(to_token_denom(10)), U128(to_token_denom(150)));
+
+    testing_env!(get_context(bob(), 0));
+    contract.accept_controller(pool_id);
+}
+
+#[test]
+fn test_controller_changed_event() {
+    let log = logger::to_log_string(Event::ControllerChanged(ControllerEvent {
+        pool_id: U64(1),
+        controller: alice(),
+        new_controller: bob()
+    }));
+
+    assert_eq!(
+        log,
+        format!(r#"{{"version":"{}","type":"controller_changed","params":{{"pool_id":"1","controller":"alice","new_controller":"bob"}}}}"#, EVENT_VERSION)
+    );
+}
+
+#[test]
+#[should_panic(expected = "ERR_NOT_WHITELISTED")]
+fn test_private_pool_rejects_lp() {
+    let (mut contract, pool_id) = create_pool(true);
+    contract.set_lp_whitelist_enabled(pool_id, true);
+    assert!(contract.pool_lp_whitelist_enabled(pool_id));
+
+    testing_env!(get_context(bob(), 0));
+    join(&mut contract, pool_id);
+}
+
+#[test]
+fn test_private_pool_accepts_whitelisted_lp() {
+    let (mut contract, pool_id) = create_pool(true);
+    contract.set_lp_whitelist_enabled(pool_id, true);
+    contract.add_to_lp_whitelist(pool_id, vec![bob()]);
+
+    assert!(contract.pool_is_whitelisted_lp(pool_id, &alice()));
+    assert!(contract.pool_is_whitelisted_lp(pool_id, &bob()));
+    assert!(!contract.pool_is_whitelisted_lp(pool_id, &carol()));
+    assert_eq!(contract.get_pool_lp_whitelist(pool_id, U64(0), U64(10)), vec![bob()]);
+
+    testing_env!(get_context(bob(), 0));
+    join(&mut contract, pool_id);
+    assert!(u128::from(contract.get_pool_token_balance(pool_id, &bob())) > 0);
+}
+
+#[test]
+fn test_remove_from_lp_whitelist() {
+    let (mut contract, pool_id) = create_pool(true);
+    contract.set_lp_whitelist_enabled(pool_id, true);
+    contract.add_to_lp_whitelist(pool_id, vec![bob(), carol()]);
+    contract.remove_from_lp_whitelist(pool_id, vec![bob()]);
+
+    assert!(!contract.pool_is_whitelisted_lp(pool_id, &bob()));
+    assert_eq!(contract.get_pool_lp_whitelist(pool_id, U64(0), U64(10)), vec![carol()]);
+
+    // Everyone can join once the whitelist is disabled
+    contract.set_lp_whitelist_enabled(pool_id, false);
+    assert!(contract.pool_is_whitelisted_lp(pool_id, &bob()));
+}
+
+#[test]
+#[should_panic(expected = "ERR_NO_CONTROLLER")]
+fn test_add_to_lp_whitelist_not_controller() {
+    let (mut contract, pool_id) = create_pool(true);
+    testing_env!(get_context(bob(), 0));
+    contract.add_to_lp_whitelist(pool_id, vec![bob()]);
+}
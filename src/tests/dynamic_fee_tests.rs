@@ -0,0 +1,173 @@
+use super::*;
+use crate::constants::MAX_FEE;
+use crate::pool::DynamicFeeInfo;
+
+const DECAY_PERIOD: u64 = 1000;
+
+fn max_fee() -> U128 {
+    U128(to_token_denom(5) / 100)
+}
+
+fn sensitivity() -> U128 {
+    U128(to_token_denom(1) / 10)
+}
+
+fn create_pool(sensitivity: U128) -> (PoolFactory, U64) {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    let pool_id = contract.new_pool(swap_fee());
+
+    for token in &[token_a(), token_b()] {
+        bind_pool(&mut contract, 
+            pool_id,
+            token,
+            U128(to_token_denom(10)),
+            U128(to_token_denom(100))
+        );
+    }
+    contract.finalize_pool(pool_id);
+    contract.set_dynamic_fee(pool_id, max_fee(), sensitivity, U64(DECAY_PERIOD));
+
+    (contract, pool_id)
+}
+
+// Moves the spot price of a in b by ~20.97% at t 0
+fn swap_a_for_b(contract: &mut PoolFactory, pool_id: U64) {
+    testing_env!(get_context(bob(), 0));
+    contract.swap_exact_amount_in(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        &token_b(),
+        U128(0),
+        U128(to_token_denom(1000))
+    );
+
+    testing_env!(get_context(alice(), 0));
+    contract.unlock_pool(pool_id);
+}
+
+#[test]
+fn test_swap_raises_fee_from_next_block() {
+    let (mut contract, pool_id) = create_pool(sensitivity());
+    swap_a_for_b(&mut contract, pool_id);
+
+    assert_eq!(contract.pool_get_swap_fee(pool_id), swap_fee());
+
+    // A 20.967% move times 0.1 sensitivity, halfway through its decay
+    testing_env!(get_context(bob(), DECAY_PERIOD / 2));
+    let surcharge = 10_483_500_000_000_000;
+    assert_eq!(contract.pool_get_swap_fee(pool_id), U128(u128::from(swap_fee()) + surcharge));
+    assert_eq!(contract.get_pool_dynamic_fee(pool_id), Some(DynamicFeeInfo {
+        base_fee: swap_fee(),
+        max_fee: max_fee(),
+        sensitivity: sensitivity(),
+        decay_period: U64(DECAY_PERIOD),
+        surcharge: U128(surcharge)
+    }));
+}
+
+#[test]
+fn test_fee_decays_to_base_fee() {
+    let (mut contract, pool_id) = create_pool(sensitivity());
+    swap_a_for_b(&mut contract, pool_id);
+
+    testing_env!(get_context(bob(), DECAY_PERIOD - 1));
+    assert!(u128::from(contract.pool_get_swap_fee(pool_id)) > u128::from(swap_fee()));
+
+    testing_env!(get_context(bob(), DECAY_PERIOD));
+    assert_eq!(contract.pool_get_swap_fee(pool_id), swap_fee());
+}
+
+#[test]
+fn test_fee_is_capped_at_max_fee() {
+    let (mut contract, pool_id) = create_pool(U128(to_token_denom(1)));
+    swap_a_for_b(&mut contract, pool_id);
+
+    // One nanosecond into the decay of a surcharge clamped to `max_fee - base_fee`
+    let max_surcharge = u128::from(max_fee()) - u128::from(swap_fee());
+    testing_env!(get_context(bob(), 1));
+    assert_eq!(
+        contract.pool_get_swap_fee(pool_id),
+        U128(u128::from(swap_fee()) + max_surcharge * u128::from(DECAY_PERIOD - 1) / u128::from(DECAY_PERIOD))
+    );
+}
+
+#[test]
+fn test_large_surcharge_decays_from_max_fee() {
+    let (mut contract, pool_id) = create_pool(U128(to_token_denom(1)));
+    swap_a_for_b(&mut contract, pool_id);
+
+    // The ~21% surcharge is stored as `max_fee - base_fee`, so the fee drops below max_fee right away
+    let max_surcharge = u128::from(max_fee()) - u128::from(swap_fee());
+    testing_env!(get_context(bob(), DECAY_PERIOD / 2));
+    assert_eq!(contract.pool_get_swap_fee(pool_id), U128(u128::from(swap_fee()) + max_surcharge / 2));
+    assert_eq!(contract.get_pool_dynamic_fee(pool_id).map(|info| info.surcharge), Some(U128(max_surcharge / 2)));
+
+    testing_env!(get_context(bob(), DECAY_PERIOD));
+    assert_eq!(contract.pool_get_swap_fee(pool_id), swap_fee());
+}
+
+#[test]
+fn test_failed_swap_leaves_fee_unchanged() {
+    let (mut contract, pool_id) = create_pool(sensitivity());
+    testing_env!(get_context(bob(), 0));
+    let (token_amount_out, _) = contract.swap_exact_amount_in(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        &token_b(),
+        U128(0),
+        U128(to_token_denom(1000))
+    );
+
+    testing_env_with_promise_results(get_context(alice(), 10), promise_results(&[false]));
+    contract.resolve_swap(pool_id, bob(), token_a(), U128(to_token_denom(10)), token_b(), token_amount_out, U128(0));
+
+    testing_env!(get_context(bob(), DECAY_PERIOD / 2));
+    assert_eq!(contract.pool_get_swap_fee(pool_id), swap_fee());
+    assert_eq!(contract.get_pool_dynamic_fee(pool_id).unwrap().surcharge, U128(0));
+}
+
+#[test]
+fn test_disable_dynamic_fee() {
+    let (mut contract, pool_id) = create_pool(sensitivity());
+    swap_a_for_b(&mut contract, pool_id);
+
+    testing_env!(get_context(alice(), 1));
+    contract.disable_dynamic_fee(pool_id);
+
+    assert_eq!(contract.pool_get_swap_fee(pool_id), swap_fee());
+    assert_eq!(contract.get_pool_dynamic_fee(pool_id), None);
+}
+
+#[test]
+#[should_panic(expected = "ERR_NO_CONTROLLER")]
+fn test_set_dynamic_fee_not_controller() {
+    let (mut contract, pool_id) = create_pool(sensitivity());
+    testing_env!(get_context(bob(), 0));
+    contract.set_dynamic_fee(pool_id, max_fee(), sensitivity(), U64(DECAY_PERIOD));
+}
+
+#[test]
+#[should_panic(expected = "ERR_MAX_FEE")]
+fn test_set_dynamic_fee_above_max_fee() {
+    let (mut contract, pool_id) = create_pool(sensitivity());
+    contract.set_dynamic_fee(pool_id, U128(MAX_FEE + 1), sensitivity(), U64(DECAY_PERIOD));
+}
+
+#[test]
+#[should_panic(expected = "ERR_MIN_FEE")]
+fn test_set_dynamic_fee_below_base_fee() {
+    let (mut contract, pool_id) = create_pool(sensitivity());
+    contract.set_dynamic_fee(pool_id, U128(u128::from(swap_fee()) - 1), sensitivity(), U64(DECAY_PERIOD));
+}
+
+#[test]
+#[should_panic(expected = "ERR_INVALID_DECAY_PERIOD")]
+fn test_set_dynamic_fee_without_decay_period() {
+    let (mut contract, pool_id) = create_pool(sensitivity());
+    contract.set_dynamic_fee(pool_id, max_fee(), sensitivity(), U64(0));
+}
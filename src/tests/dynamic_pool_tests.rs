@@ -11,7 +11,7 @@ fn create_even_pool() -> (PoolFactory, U64) {
     let pool_id = contract.new_pool(swap_fee());
 
     // Token a is stable coin worth $1
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_a(),
         U128(to_token_denom(20)),
@@ -19,7 +19,7 @@ fn create_even_pool() -> (PoolFactory, U64) {
     );
 
     // Token b is governance token worth $200
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_b(),
         U128(to_token_denom(10)),
@@ -48,6 +48,7 @@ fn test_pool_join() {
     let pool_amount_out = to_token_denom(100);
     let max_amounts_in = vec![U128(to_token_denom(100)), U128(to_token_denom(1))];
     contract.join_pool(pool_id, U128(pool_amount_out), max_amounts_in);
+    resolve_join(&mut contract, pool_id, bob(), vec![(token_a(), to_token_denom(100)), (token_b(), to_token_denom(1))], pool_amount_out);
 
     let expected_total_supply = to_token_denom(200);
     let total_supply: u128 = contract.get_pool_token_total_supply(pool_id).into();
@@ -71,6 +72,32 @@ fn test_pool_join() {
     assert_eq!(pool_mkr_balance, expected_pool_mkr_balance);
 }
 
+#[test]
+fn test_failed_join_mints_nothing() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    testing_env!(get_context(bob(), 0));
+
+    let pool_amount_out = to_token_denom(100);
+    let max_amounts_in = vec![U128(to_token_denom(100)), U128(to_token_denom(1))];
+    contract.join_pool(pool_id, U128(pool_amount_out), max_amounts_in.to_vec());
+    assert_eq!(contract.get_pool_token_balance(pool_id, &bob()), U128(0));
+
+    // Pulling token b failed, the token a that was pulled is refunded
+    testing_env_with_promise_results(get_context(alice(), 0), promise_results(&[true, false]));
+    contract.resolve_join(pool_id, bob(), vec![token_a(), token_b()], max_amounts_in, U128(pool_amount_out));
+
+    let joined_pool_tokens: u128 = contract.get_pool_token_balance(pool_id, &bob()).into();
+    let total_supply: u128 = contract.get_pool_token_total_supply(pool_id).into();
+    assert_eq!(joined_pool_tokens, 0);
+    assert_eq!(total_supply, INIT_POOL_SUPPLY);
+
+    let pool_dai_balance: u128 = contract.get_pool_balance(pool_id, &token_a()).into();
+    let pool_mkr_balance: u128 = contract.get_pool_balance(pool_id, &token_b()).into();
+    assert_eq!(pool_dai_balance, to_token_denom(100));
+    assert_eq!(pool_mkr_balance, to_token_denom(1));
+}
+
 #[test]
 fn test_pool_exit() {
     let  (mut contract, pool_id) = create_even_pool();
@@ -89,6 +116,7 @@ fn test_pool_exit() {
     let pool_amount_out = to_token_denom(100);
     let max_amounts_in = vec![U128(to_token_denom(100)), U128(to_token_denom(1))];
     contract.join_pool(pool_id, U128(pool_amount_out), max_amounts_in);
+    resolve_join(&mut contract, pool_id, bob(), vec![(token_a(), to_token_denom(100)), (token_b(), to_token_denom(1))], pool_amount_out);
 
     let owner_pool_tokens: u128 = contract.get_pool_token_balance(pool_id, &alice()).into();
     let joined_pool_tokens: u128 = contract.get_pool_token_balance(pool_id, &bob()).into();
@@ -103,11 +131,12 @@ fn test_pool_exit() {
     contract.exit_pool(pool_id, pool_tokens_in, min_amounts_out);
 
     let owner_pool_tokens_after_exit: u128 = contract.get_pool_token_balance(pool_id, &alice()).into();
-    // TODO: check if pool tokens are burned correctly
-    // let joined_pool_tokens_after_exit: u128 = contract.get_pool_token_balance(pool_id, &bob()).into();
+    let joined_pool_tokens_after_exit: u128 = contract.get_pool_token_balance(pool_id, &bob()).into();
+    let total_supply_after_exit: u128 = contract.get_pool_token_total_supply(pool_id).into();
     
     assert_eq!(owner_pool_tokens_after_exit, INIT_POOL_SUPPLY);
-    // assert_eq!(joined_pool_tokens_after_exit, 0); // First need to implement burn / internal_transfers
+    assert_eq!(joined_pool_tokens_after_exit, 0);
+    assert_eq!(total_supply_after_exit, INIT_POOL_SUPPLY);
 
     /* Test pooled tokens balances */
     let pool_dai_balance: u128 = contract.get_pool_balance(pool_id, &token_a()).into();
@@ -118,4 +147,216 @@ fn test_pool_exit() {
 
     assert_eq!(pool_dai_balance, expected_pool_dai_balance);
     assert_eq!(pool_mkr_balance, expected_pool_mkr_balance);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_join_swap_extern_amount_in() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    testing_env!(get_context(bob(), 0));
+
+    let pool_amount_out = contract.join_swap_extern_amount_in(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        U128(0)
+    );
+
+    let expected_pool_amount_out = 6553765382807621500;
+    assert_eq!(pool_amount_out, U128(expected_pool_amount_out));
+    resolve_join(&mut contract, pool_id, bob(), vec![(token_a(), to_token_denom(10))], expected_pool_amount_out);
+
+    let joined_pool_tokens: u128 = contract.get_pool_token_balance(pool_id, &bob()).into();
+    let total_supply: u128 = contract.get_pool_token_total_supply(pool_id).into();
+    assert_eq!(joined_pool_tokens, expected_pool_amount_out);
+    assert_eq!(total_supply, INIT_POOL_SUPPLY + expected_pool_amount_out);
+
+    let pool_dai_balance: u128 = contract.get_pool_balance(pool_id, &token_a()).into();
+    let pool_mkr_balance: u128 = contract.get_pool_balance(pool_id, &token_b()).into();
+    assert_eq!(pool_dai_balance, to_token_denom(110));
+    assert_eq!(pool_mkr_balance, to_token_denom(1));
+}
+
+#[test]
+#[should_panic(expected = "ERR_LIMIT_OUT")]
+fn test_join_swap_extern_amount_in_limit_out() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    testing_env!(get_context(bob(), 0));
+
+    contract.join_swap_extern_amount_in(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(7))
+    );
+}
+
+#[test]
+#[should_panic(expected = "ERR_MAX_IN_RATIO")]
+fn test_join_swap_extern_amount_in_max_in_ratio() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    testing_env!(get_context(bob(), 0));
+
+    contract.join_swap_extern_amount_in(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(51)),
+        U128(0)
+    );
+}
+
+#[test]
+fn test_join_swap_pool_amount_out() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    testing_env!(get_context(bob(), 0));
+
+    let pool_amount_out = to_token_denom(10);
+    let token_amount_in = contract.join_swap_pool_amount_out(
+        pool_id,
+        &token_a(),
+        U128(pool_amount_out),
+        U128(to_token_denom(16))
+    );
+
+    let expected_token_amount_in = 15384357656467044444;
+    assert_eq!(token_amount_in, U128(expected_token_amount_in));
+    resolve_join(&mut contract, pool_id, bob(), vec![(token_a(), expected_token_amount_in)], pool_amount_out);
+
+    let joined_pool_tokens: u128 = contract.get_pool_token_balance(pool_id, &bob()).into();
+    assert_eq!(joined_pool_tokens, pool_amount_out);
+
+    let pool_dai_balance: u128 = contract.get_pool_balance(pool_id, &token_a()).into();
+    assert_eq!(pool_dai_balance, to_token_denom(100) + expected_token_amount_in);
+}
+
+#[test]
+#[should_panic(expected = "ERR_LIMIT_IN")]
+fn test_join_swap_pool_amount_out_limit_in() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    testing_env!(get_context(bob(), 0));
+
+    contract.join_swap_pool_amount_out(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(15))
+    );
+}
+
+#[test]
+fn test_exit_swap_pool_amount_in() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    let pool_amount_in = to_token_denom(10);
+    let token_amount_out = contract.exit_swap_pool_amount_in(
+        pool_id,
+        &token_a(),
+        U128(pool_amount_in),
+        U128(to_token_denom(14))
+    );
+
+    let expected_token_amount_out = 14603884672187033804;
+    assert_eq!(token_amount_out, U128(expected_token_amount_out));
+
+    let total_supply: u128 = contract.get_pool_token_total_supply(pool_id).into();
+    assert_eq!(total_supply, INIT_POOL_SUPPLY - pool_amount_in);
+
+    let pool_dai_balance: u128 = contract.get_pool_balance(pool_id, &token_a()).into();
+    let pool_mkr_balance: u128 = contract.get_pool_balance(pool_id, &token_b()).into();
+    assert_eq!(pool_dai_balance, to_token_denom(100) - expected_token_amount_out);
+    assert_eq!(pool_mkr_balance, to_token_denom(1));
+}
+
+#[test]
+#[should_panic(expected = "ERR_LIMIT_OUT")]
+fn test_exit_swap_pool_amount_in_limit_out() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    contract.exit_swap_pool_amount_in(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(15))
+    );
+}
+
+#[test]
+fn test_exit_swap_extern_amount_out() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    let token_amount_out = to_token_denom(10);
+    let pool_amount_in = contract.exit_swap_extern_amount_out(
+        pool_id,
+        &token_a(),
+        U128(token_amount_out),
+        U128(to_token_denom(7))
+    );
+
+    let expected_pool_amount_in = 6789936821763136300;
+    assert_eq!(pool_amount_in, U128(expected_pool_amount_in));
+
+    let total_supply: u128 = contract.get_pool_token_total_supply(pool_id).into();
+    assert_eq!(total_supply, INIT_POOL_SUPPLY - expected_pool_amount_in);
+
+    let pool_dai_balance: u128 = contract.get_pool_balance(pool_id, &token_a()).into();
+    assert_eq!(pool_dai_balance, to_token_denom(90));
+}
+
+#[test]
+#[should_panic(expected = "ERR_MAX_OUT_RATIO")]
+fn test_exit_swap_extern_amount_out_max_out_ratio() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    contract.exit_swap_extern_amount_out(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(40)),
+        U128(to_token_denom(100))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Not enough balance")]
+fn test_repeated_exit_fails() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    testing_env!(get_context(bob(), 0));
+
+    let max_amounts_in = vec![U128(to_token_denom(100)), U128(to_token_denom(1))];
+    contract.join_pool(pool_id, U128(to_token_denom(100)), max_amounts_in);
+    resolve_join(&mut contract, pool_id, bob(), vec![(token_a(), to_token_denom(100)), (token_b(), to_token_denom(1))], to_token_denom(100));
+
+    let min_amounts_out = vec![U128(0), U128(0)];
+    contract.exit_pool(pool_id, U128(to_token_denom(100)), min_amounts_out.to_vec());
+
+    // Bob's pool tokens are burned, exiting again would drain alice's share
+    contract.exit_pool(pool_id, U128(to_token_denom(100)), min_amounts_out);
+}
+
+#[test]
+#[should_panic(expected = "Not enough balance")]
+fn test_exit_without_pool_tokens_fails() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    testing_env!(get_context(bob(), 0));
+
+    contract.exit_pool(pool_id, U128(to_token_denom(10)), vec![U128(0), U128(0)]);
+}
+
+#[test]
+#[should_panic(expected = "Not enough balance")]
+fn test_repeated_single_asset_exit_fails() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    testing_env!(get_context(bob(), 0));
+
+    let pool_tokens: u128 = contract.join_swap_extern_amount_in(pool_id, &token_a(), U128(to_token_denom(10)), U128(0)).into();
+    resolve_join(&mut contract, pool_id, bob(), vec![(token_a(), to_token_denom(10))], pool_tokens);
+
+    contract.exit_swap_pool_amount_in(pool_id, &token_a(), U128(pool_tokens), U128(0));
+    contract.exit_swap_pool_amount_in(pool_id, &token_a(), U128(pool_tokens), U128(0));
+}
@@ -0,0 +1,133 @@
+use super::*;
+use crate::constants::{
+    INIT_POOL_SUPPLY,
+    MAX_EXIT_FEE
+};
+
+fn exit_fee() -> U128 {
+    U128(to_token_denom(1) / 100)
+}
+
+// Bob controls the pool and holds all of its pool tokens, alice owns the factory
+fn create_pool() -> (PoolFactory, U64) {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    testing_env!(get_context(bob(), 0));
+    let pool_id = contract.new_pool(swap_fee());
+
+    bind_pool(&mut contract, 
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(20)),
+        U128(to_token_denom(100))
+    );
+    bind_pool(&mut contract, 
+        pool_id,
+        &token_b(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(1))
+    );
+    contract.set_exit_fee(pool_id, exit_fee());
+    contract.finalize_pool(pool_id);
+
+    (contract, pool_id)
+}
+
+#[test]
+fn test_set_exit_fee() {
+    let (contract, pool_id) = create_pool();
+    assert_eq!(contract.get_pool_exit_fee(pool_id), exit_fee());
+}
+
+#[test]
+fn test_exit_pool_pays_exit_fee_to_owner() {
+    let (mut contract, pool_id) = create_pool();
+
+    contract.exit_pool(pool_id, U128(to_token_denom(50)), vec![U128(0), U128(0)]);
+
+    // 1% of the 50 pool tokens goes to the owner, the other 49.5 are burned and paid out
+    let exit_fee = to_token_denom(1) / 2;
+    assert_eq!(contract.get_pool_token_balance(pool_id, &alice()), U128(exit_fee));
+    assert_eq!(contract.get_pool_token_balance(pool_id, &bob()), U128(to_token_denom(50)));
+    assert_eq!(contract.get_pool_token_total_supply(pool_id), U128(INIT_POOL_SUPPLY - to_token_denom(50) + exit_fee));
+
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(505) / 10));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_b()), U128(to_token_denom(505) / 1000));
+}
+
+#[test]
+fn test_exit_swap_pool_amount_in_pays_exit_fee_to_owner() {
+    let (mut contract, pool_id) = create_pool();
+
+    let pool_amount_in = to_token_denom(10);
+    let token_amount_out = contract.exit_swap_pool_amount_in(
+        pool_id,
+        &token_a(),
+        U128(pool_amount_in),
+        U128(0)
+    );
+
+    // Pays out less than the 14603884672187033804 a fee-less exit does
+    let expected_token_amount_out = 14461684998350318472;
+    assert_eq!(token_amount_out, U128(expected_token_amount_out));
+
+    let exit_fee = to_token_denom(1) / 10;
+    assert_eq!(contract.get_pool_token_balance(pool_id, &alice()), U128(exit_fee));
+    assert_eq!(contract.get_pool_token_balance(pool_id, &bob()), U128(INIT_POOL_SUPPLY - pool_amount_in));
+    assert_eq!(contract.get_pool_token_total_supply(pool_id), U128(INIT_POOL_SUPPLY - pool_amount_in + exit_fee));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(100) - expected_token_amount_out));
+}
+
+#[test]
+fn test_exit_swap_extern_amount_out_pays_exit_fee_to_owner() {
+    let (mut contract, pool_id) = create_pool();
+
+    let pool_amount_in = contract.exit_swap_extern_amount_out(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(7))
+    );
+
+    // Takes more than the 6789936821763136300 pool tokens a fee-less exit burns
+    let expected_pool_amount_in = 6858522042184986162;
+    let exit_fee = 68585220421849862;
+    assert_eq!(pool_amount_in, U128(expected_pool_amount_in));
+
+    assert_eq!(contract.get_pool_token_balance(pool_id, &alice()), U128(exit_fee));
+    assert_eq!(contract.get_pool_token_balance(pool_id, &bob()), U128(INIT_POOL_SUPPLY - expected_pool_amount_in));
+    assert_eq!(contract.get_pool_token_total_supply(pool_id), U128(INIT_POOL_SUPPLY - expected_pool_amount_in + exit_fee));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(90)));
+}
+
+#[test]
+#[should_panic(expected = "ERR_MAX_EXIT_FEE")]
+fn test_set_exit_fee_above_max() {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+    let pool_id = contract.new_pool(swap_fee());
+
+    contract.set_exit_fee(pool_id, U128(MAX_EXIT_FEE + 1));
+}
+
+#[test]
+#[should_panic(expected = "ERR_IS_FINALIZED")]
+fn test_set_exit_fee_after_finalize() {
+    let (mut contract, pool_id) = create_pool();
+    contract.set_exit_fee(pool_id, U128(0));
+}
+
+#[test]
+#[should_panic(expected = "ERR_NO_CONTROLLER")]
+fn test_set_exit_fee_not_controller() {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+    let pool_id = contract.new_pool(swap_fee());
+
+    testing_env!(get_context(bob(), 0));
+    contract.set_exit_fee(pool_id, exit_fee());
+}
@@ -0,0 +1,169 @@
+use super::*;
+use crate::pool_factory::ExecutedSwap;
+
+fn create_pool() -> (PoolFactory, U64) {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    let pool_id = contract.new_pool(swap_fee());
+
+    for token in &[token_a(), token_b()] {
+        bind_pool(&mut contract, 
+            pool_id,
+            token,
+            U128(to_token_denom(10)),
+            U128(to_token_denom(100))
+        );
+    }
+    contract.finalize_pool(pool_id);
+
+    testing_env!(get_context(bob(), 0));
+    (contract, pool_id)
+}
+
+fn flash_swap(contract: &mut PoolFactory, pool_id: U64) -> U128 {
+    contract.flash_swap(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(20)),
+        &token_b(),
+        U128(to_token_denom(10)),
+        carol(),
+        String::new()
+    )
+}
+
+#[test]
+fn test_flash_swap_is_priced_as_swap_exact_amount_out() {
+    let (mut contract, pool_id) = create_pool();
+    let preview = contract.get_pool_in_given_out(pool_id, &token_a(), &token_b(), U128(to_token_denom(10))).unwrap();
+
+    let token_amount_in = flash_swap(&mut contract, pool_id);
+
+    assert_eq!(token_amount_in, preview.token_amount_in);
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(100) + u128::from(token_amount_in)));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_b()), U128(to_token_denom(90)));
+    assert!(contract.pool_is_locked(pool_id));
+}
+
+#[test]
+#[should_panic(expected = "ERR_REENTRY")]
+fn test_swap_while_flash_swap_pending() {
+    let (mut contract, pool_id) = create_pool();
+    flash_swap(&mut contract, pool_id);
+
+    contract.swap_exact_amount_in(pool_id, &token_b(), U128(to_token_denom(1)), &token_a(), U128(0), U128(to_token_denom(1000)));
+}
+
+#[test]
+#[should_panic(expected = "ERR_REENTRY")]
+fn test_flash_swap_while_flash_swap_pending() {
+    let (mut contract, pool_id) = create_pool();
+    flash_swap(&mut contract, pool_id);
+    flash_swap(&mut contract, pool_id);
+}
+
+#[test]
+fn test_locked_pool_is_not_quoted() {
+    let (mut contract, pool_id) = create_pool();
+    flash_swap(&mut contract, pool_id);
+
+    let quote = contract.quote_best_route(token_a(), token_b(), U128(to_token_denom(1)), U64(1));
+    assert!(quote.routes.is_empty());
+    assert_eq!(quote.total_amount_out, U128(0));
+}
+
+#[test]
+#[should_panic(expected = "ERR_LIMIT_IN")]
+fn test_flash_swap_limit_in() {
+    let (mut contract, pool_id) = create_pool();
+    contract.flash_swap(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        &token_b(),
+        U128(to_token_denom(10)),
+        carol(),
+        String::new()
+    );
+}
+
+fn executed_swap(pool_id: U64, token_amount_in: U128) -> ExecutedSwap {
+    ExecutedSwap {
+        pool_id,
+        token_in: token_a(),
+        token_out: token_b(),
+        token_amount_in,
+        token_amount_out: U128(to_token_denom(10)),
+        protocol_fee_amount: U128(0)
+    }
+}
+
+// Stands in for the transfer of `token_out` to carol, the receiver is called and the repayment pulled afterwards
+fn resolve_transfer(contract: &mut PoolFactory, pool_id: U64, token_amount_in: U128, success: bool) {
+    testing_env_with_promise_results(get_context(alice(), 0), promise_results(&[success]));
+    contract.resolve_flash_swap_transfer(bob(), carol(), executed_swap(pool_id, token_amount_in), String::new());
+}
+
+// Stands in for pulling the repayment from carol once `on_flash_swap` returned
+fn resolve_repayment(contract: &mut PoolFactory, pool_id: U64, token_amount_in: U128, success: bool) {
+    testing_env_with_promise_results(get_context(alice(), 0), promise_results(&[success]));
+    contract.resolve_flash_swap(carol(), executed_swap(pool_id, token_amount_in));
+}
+
+#[test]
+#[should_panic(expected = "ERR_PRIVATE_METHOD")]
+fn test_resolve_flash_swap_is_private() {
+    let (mut contract, pool_id) = create_pool();
+    contract.resolve_flash_swap(carol(), executed_swap(pool_id, U128(to_token_denom(1))));
+}
+
+#[test]
+fn test_failed_flash_swap_transfer_is_rolled_back() {
+    let (mut contract, pool_id) = create_pool();
+    let token_amount_in = flash_swap(&mut contract, pool_id);
+
+    // Sending `token_out` to carol failed, nothing left the factory
+    resolve_transfer(&mut contract, pool_id, token_amount_in, false);
+
+    assert!(!contract.pool_is_locked(pool_id));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(100)));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_b()), U128(to_token_denom(100)));
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(100)));
+    assert_eq!(contract.get_custody_balance(&token_b()), U128(to_token_denom(100)));
+}
+
+#[test]
+fn test_repaid_flash_swap() {
+    let (mut contract, pool_id) = create_pool();
+    let token_amount_in = flash_swap(&mut contract, pool_id);
+
+    resolve_transfer(&mut contract, pool_id, token_amount_in, true);
+    // The pool stays locked while the receiver is called and the repayment is pulled
+    assert!(contract.pool_is_locked(pool_id));
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(100) + u128::from(token_amount_in)));
+    assert_eq!(contract.get_custody_balance(&token_b()), U128(to_token_denom(90)));
+
+    resolve_repayment(&mut contract, pool_id, token_amount_in, true);
+    assert!(!contract.pool_is_locked(pool_id));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(100) + u128::from(token_amount_in)));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_b()), U128(to_token_denom(90)));
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(100) + u128::from(token_amount_in)));
+}
+
+#[test]
+fn test_unpaid_flash_swap_is_rolled_back() {
+    let (mut contract, pool_id) = create_pool();
+    let token_amount_in = flash_swap(&mut contract, pool_id);
+
+    resolve_transfer(&mut contract, pool_id, token_amount_in, true);
+    // Carol didn't repay, the pool only keeps what's actually in custody
+    resolve_repayment(&mut contract, pool_id, token_amount_in, false);
+
+    assert!(!contract.pool_is_locked(pool_id));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(100)));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_b()), U128(to_token_denom(90)));
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(100)));
+    assert_eq!(contract.get_custody_balance(&token_b()), U128(to_token_denom(90)));
+}
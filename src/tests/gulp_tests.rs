@@ -0,0 +1,129 @@
+use super::*;
+
+fn create_pool() -> (PoolFactory, U64) {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    let pool_id = contract.new_pool(swap_fee());
+
+    bind_pool(&mut contract, 
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(100))
+    );
+
+    bind_pool(&mut contract, 
+        pool_id,
+        &token_b(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(100))
+    );
+    contract.finalize_pool(pool_id);
+
+    (contract, pool_id)
+}
+
+#[test]
+fn test_custody_tracks_pulls() {
+    let (mut contract, pool_id) = create_pool();
+
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(100)));
+    assert_eq!(contract.get_custody_balance(&token_b()), U128(to_token_denom(100)));
+    assert_eq!(contract.get_custody_balance(&token_c()), U128(0));
+
+    testing_env!(get_context(bob(), 0));
+    contract.join_pool(
+        pool_id,
+        U128(to_token_denom(10)),
+        vec![U128(to_token_denom(10)), U128(to_token_denom(10))]
+    );
+
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(110)));
+    assert_eq!(contract.get_custody_balance(&token_b()), U128(to_token_denom(110)));
+    resolve_join(&mut contract, pool_id, bob(), vec![(token_a(), to_token_denom(10)), (token_b(), to_token_denom(10))], to_token_denom(10));
+
+    contract.swap_exact_amount_in(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(5)),
+        &token_b(),
+        U128(0),
+        U128(to_token_denom(1000))
+    );
+
+    // Payouts only leave custody once the transfer out has resolved
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(115)));
+    assert_eq!(contract.get_custody_balance(&token_b()), U128(to_token_denom(110)));
+}
+
+#[test]
+#[should_panic(expected = "ERR_NOT_BOUND")]
+fn test_gulp_unbound_token() {
+    let (mut contract, pool_id) = create_pool();
+    contract.gulp(pool_id, &token_c());
+}
+
+#[test]
+#[should_panic(expected = "ERR_NOT_OWNER")]
+fn test_gulp_not_owner() {
+    let (mut contract, pool_id) = create_pool();
+    testing_env!(get_context(bob(), 0));
+    contract.gulp(pool_id, &token_a());
+}
+
+#[test]
+fn test_gulp_locks_until_resolved() {
+    let (mut contract, pool_id) = create_pool();
+    contract.gulp(pool_id, &token_a());
+    assert!(contract.pool_is_locked(pool_id));
+
+    // Reading the balance failed, nothing is absorbed but the pool is released
+    testing_env_with_promise_results(get_context(alice(), 0), promise_results(&[false]));
+    assert_eq!(contract.resolve_gulp(pool_id, token_a()), U128(0));
+    assert!(!contract.pool_is_locked(pool_id));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(100)));
+}
+
+#[test]
+#[should_panic(expected = "ERR_PRIVATE_METHOD")]
+fn test_resolve_gulp_is_private() {
+    let (mut contract, pool_id) = create_pool();
+    testing_env!(get_context(bob(), 0));
+    contract.resolve_gulp(pool_id, token_a());
+}
+
+#[test]
+fn test_failed_payout_is_claimable() {
+    let (mut contract, pool_id) = create_pool();
+
+    let amounts_out = contract.preview_exit_pool(pool_id, U128(to_token_denom(10))).unwrap();
+    contract.exit_pool(pool_id, U128(to_token_denom(10)), amounts_out.clone());
+
+    // Paying out token a failed, the tokens never left the factory
+    testing_env_with_promise_results(get_context(alice(), 0), promise_results(&[false]));
+    contract.resolve_transfer_out(pool_id, token_a(), alice(), amounts_out[0]);
+
+    assert_eq!(contract.get_claimable(&alice(), &token_a()), amounts_out[0]);
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(100)));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(100) - u128::from(amounts_out[0])));
+
+    testing_env!(get_context(alice(), 0));
+    contract.claim(token_a());
+    assert_eq!(contract.get_claimable(&alice(), &token_a()), U128(0));
+
+    // The claim failed as well, so it's credited again
+    testing_env_with_promise_results(get_context(alice(), 0), promise_results(&[false]));
+    contract.resolve_refund(token_a(), alice(), amounts_out[0]);
+    assert_eq!(contract.get_claimable(&alice(), &token_a()), amounts_out[0]);
+}
+
+#[test]
+#[should_panic(expected = "ERR_NOTHING_TO_CLAIM")]
+fn test_claim_without_failed_payouts() {
+    let (mut contract, _pool_id) = create_pool();
+
+    testing_env!(get_context(bob(), 0));
+    contract.claim(token_a());
+}
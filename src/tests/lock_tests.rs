@@ -0,0 +1,154 @@
+use super::*;
+use crate::pool::PoolError;
+
+fn create_pool() -> (PoolFactory, U64) {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    let pool_id = contract.new_pool(swap_fee());
+
+    for token in &[token_a(), token_b()] {
+        bind_pool(&mut contract, 
+            pool_id,
+            token,
+            U128(to_token_denom(10)),
+            U128(to_token_denom(100))
+        );
+    }
+    contract.finalize_pool(pool_id);
+
+    testing_env!(get_context(bob(), 0));
+    (contract, pool_id)
+}
+
+fn swap_a_for_b(contract: &mut PoolFactory, pool_id: U64) {
+    contract.swap_exact_amount_in(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(1)),
+        &token_b(),
+        U128(0),
+        U128(to_token_denom(1000))
+    );
+}
+
+#[test]
+fn test_swap_locks_pool() {
+    let (mut contract, pool_id) = create_pool();
+    assert!(!contract.pool_is_locked(pool_id));
+
+    swap_a_for_b(&mut contract, pool_id);
+
+    assert!(contract.pool_is_locked(pool_id));
+}
+
+#[test]
+#[should_panic(expected = "ERR_REENTRY")]
+fn test_second_swap_while_locked() {
+    let (mut contract, pool_id) = create_pool();
+    swap_a_for_b(&mut contract, pool_id);
+    swap_a_for_b(&mut contract, pool_id);
+}
+
+#[test]
+#[should_panic(expected = "ERR_REENTRY")]
+fn test_join_while_locked() {
+    let (mut contract, pool_id) = create_pool();
+    swap_a_for_b(&mut contract, pool_id);
+
+    contract.join_swap_extern_amount_in(pool_id, &token_a(), U128(to_token_denom(1)), U128(0));
+}
+
+#[test]
+fn test_join_locks_pool_until_resolved() {
+    let (mut contract, pool_id) = create_pool();
+    let pool_amount_out = contract.join_swap_extern_amount_in(pool_id, &token_a(), U128(to_token_denom(1)), U128(0));
+    assert!(contract.pool_is_locked(pool_id));
+
+    resolve_join(&mut contract, pool_id, bob(), vec![(token_a(), to_token_denom(1))], pool_amount_out.into());
+    assert!(!contract.pool_is_locked(pool_id));
+}
+
+#[test]
+fn test_failed_join_unlocks_pool() {
+    let (mut contract, pool_id) = create_pool();
+    let pool_amount_out = contract.join_swap_extern_amount_in(pool_id, &token_a(), U128(to_token_denom(1)), U128(0));
+
+    testing_env_with_promise_results(get_context(alice(), 0), promise_results(&[false]));
+    contract.resolve_join(pool_id, bob(), vec![token_a()], vec![U128(to_token_denom(1))], pool_amount_out);
+    assert!(!contract.pool_is_locked(pool_id));
+}
+
+#[test]
+#[should_panic(expected = "ERR_REENTRY")]
+fn test_exit_while_locked() {
+    let (mut contract, pool_id) = create_pool();
+    swap_a_for_b(&mut contract, pool_id);
+
+    testing_env!(get_context(alice(), 0));
+    contract.exit_pool(pool_id, U128(to_token_denom(1)), vec![U128(0), U128(0)]);
+}
+
+#[test]
+fn test_locked_pool_previews() {
+    let (mut contract, pool_id) = create_pool();
+    swap_a_for_b(&mut contract, pool_id);
+
+    assert_eq!(contract.get_pool_out_given_in(pool_id, &token_a(), U128(to_token_denom(1)), &token_b()), Err(PoolError::Locked));
+    assert_eq!(contract.get_pool_in_given_out(pool_id, &token_a(), &token_b(), U128(to_token_denom(1))), Err(PoolError::Locked));
+    assert_eq!(contract.preview_join_pool(pool_id, U128(to_token_denom(1))), Err(PoolError::Locked));
+    assert_eq!(contract.preview_exit_pool(pool_id, U128(to_token_denom(1))), Err(PoolError::Locked));
+}
+
+#[test]
+#[should_panic(expected = "ERR_REENTRY")]
+fn test_finalize_while_bind_pending() {
+    testing_env!(get_context(alice(), 0));
+    let mut contract = PoolFactory::init(alice());
+    let pool_id = contract.new_pool(swap_fee());
+    bind_pool(&mut contract, pool_id, &token_a(), U128(to_token_denom(10)), U128(to_token_denom(100)));
+
+    contract.bind_pool(pool_id, &token_b(), U128(to_token_denom(10)), U128(to_token_denom(100)));
+    assert!(contract.pool_is_locked(pool_id));
+    contract.finalize_pool(pool_id);
+}
+
+#[test]
+fn test_failed_bind_pull_unlocks_pool() {
+    testing_env!(get_context(alice(), 0));
+    let mut contract = PoolFactory::init(alice());
+    let pool_id = contract.new_pool(swap_fee());
+    bind_pool(&mut contract, pool_id, &token_a(), U128(to_token_denom(10)), U128(to_token_denom(100)));
+
+    contract.rebind_pool(pool_id, &token_a(), U128(to_token_denom(10)), U128(to_token_denom(150)));
+    assert!(contract.pool_is_locked(pool_id));
+
+    testing_env_with_promise_results(get_context(alice(), 0), promise_results(&[false]));
+    contract.resolve_transfer_in(pool_id, token_a(), U128(to_token_denom(50)));
+    assert!(!contract.pool_is_locked(pool_id));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(100)));
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(100)));
+}
+
+#[test]
+fn test_owner_unlocks_pool() {
+    let (mut contract, pool_id) = create_pool();
+    swap_a_for_b(&mut contract, pool_id);
+
+    testing_env!(get_context(alice(), 0));
+    contract.unlock_pool(pool_id);
+    assert!(!contract.pool_is_locked(pool_id));
+
+    testing_env!(get_context(bob(), 0));
+    swap_a_for_b(&mut contract, pool_id);
+}
+
+#[test]
+#[should_panic(expected = "ERR_NOT_OWNER")]
+fn test_unlock_pool_not_owner() {
+    let (mut contract, pool_id) = create_pool();
+    swap_a_for_b(&mut contract, pool_id);
+
+    contract.unlock_pool(pool_id);
+}
@@ -0,0 +1,71 @@
+use super::*;
+use near_sdk::serde_json;
+use crate::logger::{
+    self,
+    Event,
+    VersionedEvent,
+    NewOwnerEvent,
+    SwapEvent,
+    JoinEvent,
+    TokenAmount,
+    EVENT_VERSION
+};
+
+#[test]
+fn test_event_schema() {
+    let log = logger::to_log_string(Event::NewOwner(NewOwnerEvent {
+        previous_owner: alice(),
+        new_owner: bob()
+    }));
+
+    assert_eq!(
+        log, 
+        format!(r#"{{"version":"{}","type":"new_owner","params":{{"previous_owner":"alice","new_owner":"bob"}}}}"#, EVENT_VERSION)
+    );
+}
+
+#[test]
+fn test_event_roundtrip() {
+    let event = Event::Swap(SwapEvent {
+        pool_id: U64(1),
+        caller: bob(),
+        token_in: token_a(),
+        token_out: token_b(),
+        token_amount_in: U128(to_token_denom(25)),
+        token_amount_out: U128(199519711827096258),
+        spot_price_before: U128(to_token_denom(100)),
+        spot_price_after: U128(156626128385155466808),
+        swap_fee: swap_fee()
+    });
+    let log = logger::to_log_string(event);
+
+    let parsed: VersionedEvent = serde_json::from_str(&log).unwrap();
+    assert_eq!(parsed.version, EVENT_VERSION);
+    match parsed.event {
+        Event::Swap(swap) => {
+            assert_eq!(swap.token_amount_in, U128(to_token_denom(25)));
+            assert_eq!(swap.spot_price_after, U128(156626128385155466808));
+        },
+        _ => panic!("expected swap event")
+    }
+}
+
+#[test]
+fn test_join_event_lists_every_token() {
+    let log = logger::to_log_string(Event::Join(JoinEvent {
+        pool_id: U64(1),
+        caller: bob(),
+        tokens_in: vec![
+            TokenAmount { token: token_a(), amount: U128(100) },
+            TokenAmount { token: token_b(), amount: U128(1) }
+        ],
+        pool_tokens_minted: U128(10)
+    }));
+
+    let parsed: serde_json::Value = serde_json::from_str(&log).unwrap();
+    assert_eq!(parsed["type"], "join");
+    assert_eq!(parsed["params"]["pool_id"], "1");
+    assert_eq!(parsed["params"]["tokens_in"][1]["token"], "t2");
+    assert_eq!(parsed["params"]["tokens_in"][1]["amount"], "1");
+    assert_eq!(parsed["params"]["pool_tokens_minted"], "10");
+}
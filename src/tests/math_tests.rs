@@ -6,4 +6,16 @@ fn test_pow() {
     let pow_of_2 = math::pow_u128(2, 2);
     
     assert_eq!(pow_of_2, 4);
-}
\ No newline at end of file
+}
+
+// The integer part of the exponent is taken as a whole power and only the remainder is approximated
+#[test]
+fn test_pow_fractional_exponent() {
+    // 1.5 ^ 0.5, the integer part of the base is larger than the exponent
+    let sqrt = math::pow_u128(to_token_denom(3) / 2, to_token_denom(1) / 2);
+    assert_eq!(sqrt, 1224744871413447685);
+
+    // 0.5 ^ 2.5
+    let pow = math::pow_u128(to_token_denom(1) / 2, to_token_denom(5) / 2);
+    assert_eq!(pow, 176776695311901396);
+}
@@ -0,0 +1,143 @@
+use super::*;
+use crate::oracle::Oracle;
+
+const SECOND: u64 = 1_000_000_000;
+
+fn create_pool() -> (PoolFactory, U64) {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    let pool_id = contract.new_pool(swap_fee());
+
+    bind_pool(&mut contract, 
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(100))
+    );
+
+    bind_pool(&mut contract, 
+        pool_id,
+        &token_b(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(100))
+    );
+    contract.finalize_pool(pool_id);
+
+    (contract, pool_id)
+}
+
+// The price of token a in token b is 1 for the first 10 seconds, the returned price from then on
+fn swap_at_10_seconds(contract: &mut PoolFactory, pool_id: U64) -> u128 {
+    testing_env!(get_context(bob(), 10 * SECOND));
+    contract.swap_exact_amount_in(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        &token_b(),
+        U128(0),
+        U128(to_token_denom(1000))
+    );
+
+    contract.get_pool_spot_price_sans_fee(pool_id, &token_a(), &token_b()).into()
+}
+
+#[test]
+fn test_twap_without_trades() {
+    let (contract, pool_id) = create_pool();
+
+    testing_env!(get_context(alice(), 20 * SECOND));
+    assert_eq!(contract.get_twap(pool_id, &token_a(), &token_b(), U64(20)), U128(to_token_denom(1)));
+}
+
+#[test]
+fn test_twap_weights_prices_by_time() {
+    let (mut contract, pool_id) = create_pool();
+    let price = swap_at_10_seconds(&mut contract, pool_id);
+
+    testing_env!(get_context(alice(), 20 * SECOND));
+    assert_eq!(contract.get_twap(pool_id, &token_a(), &token_b(), U64(20)), U128((to_token_denom(1) + price) / 2));
+    assert_eq!(contract.get_twap(pool_id, &token_a(), &token_b(), U64(5)), U128(price));
+
+    // The window starts before the last update, between the only observation and the cumulative prices of that update
+    assert_eq!(
+        contract.get_twap(pool_id, &token_a(), &token_b(), U64(15)),
+        U128((to_token_denom(1) * 5 + price * 10) / 15)
+    );
+
+    let reverse_price: u128 = contract.get_pool_spot_price_sans_fee(pool_id, &token_b(), &token_a()).into();
+    assert_eq!(contract.get_twap(pool_id, &token_b(), &token_a(), U64(5)), U128(reverse_price));
+}
+
+#[test]
+fn test_twap_ignores_trades_in_the_same_block() {
+    let (mut contract, pool_id) = create_pool();
+    let price = swap_at_10_seconds(&mut contract, pool_id);
+
+    // Moving the price back and forth at the end of the window doesn't change the average
+    testing_env!(get_context(alice(), 20 * SECOND));
+    contract.unlock_pool(pool_id);
+    testing_env!(get_context(bob(), 20 * SECOND));
+    contract.swap_exact_amount_in(pool_id, &token_a(), U128(to_token_denom(50)), &token_b(), U128(0), U128(to_token_denom(1000)));
+
+    assert_eq!(contract.get_twap(pool_id, &token_a(), &token_b(), U64(20)), U128((to_token_denom(1) + price) / 2));
+}
+
+#[test]
+fn test_failed_swap_leaves_twap_unchanged() {
+    let (mut contract, pool_id) = create_pool();
+    testing_env!(get_context(bob(), 10 * SECOND));
+    let (token_amount_out, _) = contract.swap_exact_amount_in(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        &token_b(),
+        U128(0),
+        U128(to_token_denom(1000))
+    );
+
+    // The pull fails a few blocks later, the pool never traded at the swap's price
+    testing_env_with_promise_results(get_context(alice(), 15 * SECOND), promise_results(&[false]));
+    contract.resolve_swap(pool_id, bob(), token_a(), U128(to_token_denom(10)), token_b(), token_amount_out, U128(0));
+
+    testing_env!(get_context(alice(), 20 * SECOND));
+    assert_eq!(contract.get_twap(pool_id, &token_a(), &token_b(), U64(20)), U128(to_token_denom(1)));
+    assert_eq!(contract.get_twap(pool_id, &token_a(), &token_b(), U64(5)), U128(to_token_denom(1)));
+}
+
+#[test]
+fn test_observations_are_throttled() {
+    testing_env!(get_context(alice(), 0));
+    let mut oracle = Oracle::new(0);
+    oracle.initialize(1, 0);
+
+    // An update every second would fill the buffer in 64 seconds if every update was observed
+    for second in 1..=200 {
+        oracle.update(&[to_token_denom(1)], second * SECOND);
+    }
+
+    assert_eq!(oracle.get_cumulative_price(0, to_token_denom(1), SECOND), Some(to_token_denom(1) * u128::from(SECOND)));
+}
+
+#[test]
+#[should_panic(expected = "ERR_INSUFFICIENT_HISTORY")]
+fn test_twap_insufficient_history() {
+    let (contract, pool_id) = create_pool();
+
+    testing_env!(get_context(alice(), 20 * SECOND));
+    contract.get_twap(pool_id, &token_a(), &token_b(), U64(30));
+}
+
+#[test]
+#[should_panic(expected = "ERR_NOT_FINALIZED")]
+fn test_twap_not_finalized() {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+    let pool_id = contract.new_pool(swap_fee());
+    bind_pool(&mut contract, pool_id, &token_a(), U128(to_token_denom(10)), U128(to_token_denom(100)));
+    bind_pool(&mut contract, pool_id, &token_b(), U128(to_token_denom(10)), U128(to_token_denom(100)));
+
+    contract.get_twap(pool_id, &token_a(), &token_b(), U64(1));
+}
@@ -0,0 +1,151 @@
+use super::*;
+use crate::constants::{INIT_POOL_SUPPLY, MIN_WEIGHT};
+
+fn collateral() -> String {
+    "collateral".to_string()
+}
+
+fn odds(percentages: &[u128]) -> Vec<U128> {
+    percentages.iter().map(|percentage| U128(to_token_denom(*percentage) / 100)).collect()
+}
+
+fn outcomes(num_outcomes: u8) -> Vec<AccountId> {
+    (0..num_outcomes).map(|i| format!("outcome_{}", i)).collect()
+}
+
+fn new_outcome_pool(outcome_tokens: Vec<AccountId>, initial_odds: Vec<U128>) -> (PoolFactory, U64) {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    let pool_id = contract.new_outcome_pool(
+        collateral(),
+        outcome_tokens,
+        initial_odds,
+        swap_fee(),
+        U128(to_token_denom(100))
+    );
+
+    (contract, pool_id)
+}
+
+// Stands in for the callback of the pulls of the collateral and every outcome token, in that order
+fn resolve_outcome_pool(contract: &mut PoolFactory, pool_id: U64, outcome_tokens: Vec<AccountId>, successes: &[bool]) {
+    let mut tokens = vec![collateral()];
+    tokens.extend(outcome_tokens);
+    testing_env_with_promise_results(get_context(alice(), 0), promise_results(successes));
+    contract.resolve_outcome_pool(pool_id, alice(), tokens);
+    testing_env!(get_context(alice(), 0));
+}
+
+fn create_outcome_pool(outcome_tokens: Vec<AccountId>, initial_odds: Vec<U128>) -> (PoolFactory, U64) {
+    let successes = vec![true; outcome_tokens.len() + 1];
+    let (mut contract, pool_id) = new_outcome_pool(outcome_tokens.to_vec(), initial_odds);
+    resolve_outcome_pool(&mut contract, pool_id, outcome_tokens, &successes);
+
+    (contract, pool_id)
+}
+
+#[test]
+fn test_binary_outcome_pool() {
+    let (contract, pool_id) = create_outcome_pool(vec![token_a(), token_b()], odds(&[60, 40]));
+
+    assert!(contract.pool_is_finalized(pool_id));
+    assert_eq!(contract.get_pool_final_tokens(pool_id), vec![collateral(), token_a(), token_b()]);
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &collateral()), U128(to_token_denom(25)));
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &token_a()), U128(to_token_denom(15)));
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &token_b()), U128(to_token_denom(10)));
+    assert_eq!(contract.get_pool_token_balance(pool_id, &alice()), U128(INIT_POOL_SUPPLY));
+
+    // Each outcome is priced at its odds in collateral
+    assert_eq!(contract.get_pool_spot_price_sans_fee(pool_id, &collateral(), &token_a()), U128(to_token_denom(60) / 100));
+    assert_eq!(contract.get_pool_spot_price_sans_fee(pool_id, &collateral(), &token_b()), U128(to_token_denom(40) / 100));
+}
+
+#[test]
+fn test_outcome_pool_is_finalized_once_pulled() {
+    let (mut contract, pool_id) = new_outcome_pool(vec![token_a(), token_b()], odds(&[60, 40]));
+
+    assert!(!contract.pool_is_finalized(pool_id));
+    assert!(contract.pool_is_locked(pool_id));
+    assert_eq!(contract.get_pool_token_balance(pool_id, &alice()), U128(0));
+    assert_eq!(contract.get_custody_balance(&collateral()), U128(to_token_denom(100)));
+
+    resolve_outcome_pool(&mut contract, pool_id, vec![token_a(), token_b()], &[true, true, true]);
+    assert!(contract.pool_is_finalized(pool_id));
+    assert!(!contract.pool_is_locked(pool_id));
+}
+
+#[test]
+fn test_failed_pull_tears_down_outcome_pool() {
+    let (mut contract, pool_id) = new_outcome_pool(vec![token_a(), token_b()], odds(&[60, 40]));
+
+    // Pulling token b failed, the collateral and token a are refunded
+    resolve_outcome_pool(&mut contract, pool_id, vec![token_a(), token_b()], &[true, true, false]);
+
+    assert!(!contract.pool_is_finalized(pool_id));
+    assert!(!contract.pool_is_locked(pool_id));
+    assert_eq!(contract.get_pool_num_tokens(pool_id), U64(0));
+    assert_eq!(contract.get_pool_token_balance(pool_id, &alice()), U128(0));
+    assert_eq!(contract.get_custody_balance(&token_b()), U128(0));
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(100)));
+}
+
+#[test]
+#[should_panic(expected = "ERR_REENTRY")]
+fn test_controller_handoff_while_outcome_pool_pending() {
+    let (mut contract, pool_id) = new_outcome_pool(vec![token_a(), token_b()], odds(&[60, 40]));
+
+    // The callback finalizes or unbinds as the sender, so it has to stay the controller
+    contract.set_controller(pool_id, bob());
+}
+
+#[test]
+fn test_outcome_pool_with_equal_odds() {
+    let (contract, pool_id) = create_outcome_pool(outcomes(4), odds(&[25, 25, 25, 25]));
+
+    assert_eq!(contract.get_pool_num_tokens(pool_id), U64(5));
+    assert_eq!(contract.get_pool_total_denormalized_weight(pool_id), U128(to_token_denom(50)));
+    for outcome in outcomes(4) {
+        assert_eq!(contract.get_pool_denormalized_weight(pool_id, &outcome), U128(to_token_denom(25) / 4));
+        assert_eq!(contract.get_pool_spot_price_sans_fee(pool_id, &collateral(), &outcome), U128(to_token_denom(1) / 4));
+    }
+}
+
+#[test]
+#[should_panic(expected = "ERR_INVALID_ODDS")]
+fn test_outcome_pool_odds_not_summing_to_one() {
+    create_outcome_pool(vec![token_a(), token_b()], odds(&[60, 50]));
+}
+
+#[test]
+#[should_panic(expected = "ERR_ODDS_LEN")]
+fn test_outcome_pool_odds_len() {
+    create_outcome_pool(vec![token_a(), token_b(), token_c()], odds(&[60, 40]));
+}
+
+#[test]
+#[should_panic(expected = "ERR_MIN_OUTCOMES")]
+fn test_outcome_pool_single_outcome() {
+    create_outcome_pool(vec![token_a()], odds(&[100]));
+}
+
+#[test]
+#[should_panic(expected = "ERR_MIN_ODDS")]
+fn test_outcome_pool_odds_below_min_odds() {
+    create_outcome_pool(vec![token_a(), token_b()], odds(&[98, 2]));
+}
+
+#[test]
+fn test_outcome_pool_min_odds() {
+    let (contract, pool_id) = create_outcome_pool(vec![token_a(), token_b()], odds(&[96, 4]));
+
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &token_b()), U128(MIN_WEIGHT));
+    assert_eq!(contract.get_pool_spot_price_sans_fee(pool_id, &collateral(), &token_b()), U128(to_token_denom(4) / 100));
+}
+
+#[test]
+#[should_panic(expected = "ERR_MAX_TOKENS")]
+fn test_outcome_pool_max_tokens() {
+    create_outcome_pool(outcomes(8), odds(&[30, 10, 10, 10, 10, 10, 10, 10]));
+}
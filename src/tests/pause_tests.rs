@@ -0,0 +1,145 @@
+use super::*;
+use crate::pool::PoolError;
+
+fn create_pool(finalize: bool) -> (PoolFactory, U64) {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    let pool_id = contract.new_pool(swap_fee());
+
+    for token in &[token_a(), token_b()] {
+        bind_pool(&mut contract, 
+            pool_id,
+            token,
+            U128(to_token_denom(10)),
+            U128(to_token_denom(100))
+        );
+    }
+    if finalize {
+        contract.finalize_pool(pool_id);
+    }
+
+    (contract, pool_id)
+}
+
+fn swap_a_for_b(contract: &mut PoolFactory, pool_id: U64) {
+    testing_env!(get_context(bob(), 0));
+    contract.swap_exact_amount_in(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(1)),
+        &token_b(),
+        U128(0),
+        U128(to_token_denom(1000))
+    );
+}
+
+#[test]
+#[should_panic(expected = "ERR_SWAP_NOT_PUBLIC")]
+fn test_swap_not_public_before_finalize() {
+    let (mut contract, pool_id) = create_pool(false);
+    assert!(!contract.pool_is_public_swap(pool_id));
+    swap_a_for_b(&mut contract, pool_id);
+}
+
+#[test]
+fn test_public_swap_before_finalize() {
+    let (mut contract, pool_id) = create_pool(false);
+    contract.set_public_swap(pool_id, true);
+    assert!(contract.pool_is_public_swap(pool_id));
+
+    swap_a_for_b(&mut contract, pool_id);
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(101)));
+}
+
+#[test]
+fn test_finalize_enables_public_swap() {
+    let (contract, pool_id) = create_pool(true);
+    assert!(contract.pool_is_public_swap(pool_id));
+}
+
+#[test]
+#[should_panic(expected = "ERR_IS_FINALIZED")]
+fn test_set_public_swap_after_finalize() {
+    let (mut contract, pool_id) = create_pool(true);
+    contract.set_public_swap(pool_id, false);
+}
+
+#[test]
+#[should_panic(expected = "ERR_NO_CONTROLLER")]
+fn test_set_public_swap_not_controller() {
+    let (mut contract, pool_id) = create_pool(false);
+    testing_env!(get_context(bob(), 0));
+    contract.set_public_swap(pool_id, true);
+}
+
+#[test]
+#[should_panic(expected = "ERR_PAUSED")]
+fn test_paused_pool_blocks_swaps() {
+    let (mut contract, pool_id) = create_pool(true);
+    contract.set_pool_paused(pool_id, true);
+    assert!(contract.pool_is_paused(pool_id));
+
+    swap_a_for_b(&mut contract, pool_id);
+}
+
+#[test]
+#[should_panic(expected = "ERR_PAUSED")]
+fn test_paused_pool_blocks_joins() {
+    let (mut contract, pool_id) = create_pool(true);
+    contract.set_pool_paused(pool_id, true);
+
+    testing_env!(get_context(bob(), 0));
+    contract.join_swap_extern_amount_in(pool_id, &token_a(), U128(to_token_denom(1)), U128(0));
+}
+
+#[test]
+fn test_paused_pool_allows_exits() {
+    let (mut contract, pool_id) = create_pool(true);
+    contract.set_pool_paused(pool_id, true);
+
+    assert_eq!(contract.get_pool_out_given_in(pool_id, &token_a(), U128(to_token_denom(1)), &token_b()), Err(PoolError::Paused));
+    assert_eq!(contract.preview_join_pool(pool_id, U128(to_token_denom(1))), Err(PoolError::Paused));
+
+    contract.exit_pool(pool_id, U128(to_token_denom(50)), vec![U128(0), U128(0)]);
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(50)));
+}
+
+#[test]
+fn test_unpause_pool() {
+    let (mut contract, pool_id) = create_pool(true);
+    contract.set_pool_paused(pool_id, true);
+    contract.set_pool_paused(pool_id, false);
+    assert!(!contract.pool_is_paused(pool_id));
+
+    swap_a_for_b(&mut contract, pool_id);
+}
+
+#[test]
+#[should_panic(expected = "ERR_PAUSED")]
+fn test_global_pause_blocks_swaps() {
+    let (mut contract, pool_id) = create_pool(true);
+    contract.set_paused(true);
+    assert!(contract.is_paused());
+    assert!(contract.pool_is_paused(pool_id));
+
+    swap_a_for_b(&mut contract, pool_id);
+}
+
+#[test]
+fn test_global_pause_allows_exits() {
+    let (mut contract, pool_id) = create_pool(true);
+    contract.set_paused(true);
+
+    assert_eq!(contract.get_pool_in_given_out(pool_id, &token_a(), &token_b(), U128(to_token_denom(1))), Err(PoolError::Paused));
+    contract.exit_swap_pool_amount_in(pool_id, &token_a(), U128(to_token_denom(10)), U128(0));
+}
+
+#[test]
+#[should_panic(expected = "ERR_NOT_OWNER")]
+fn test_pause_not_owner() {
+    let (mut contract, pool_id) = create_pool(true);
+    testing_env!(get_context(bob(), 0));
+    contract.set_pool_paused(pool_id, true);
+}
@@ -0,0 +1,105 @@
+use super::*;
+
+fn create_pool(contract: &mut PoolFactory, tokens: Vec<AccountId>) -> U64 {
+    let pool_id = contract.new_pool(swap_fee());
+
+    for token in &tokens {
+        bind_pool(contract, 
+            pool_id,
+            token,
+            U128(to_token_denom(10)),
+            U128(to_token_denom(100))
+        );
+    }
+
+    pool_id
+}
+
+fn create_pools() -> PoolFactory {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    create_pool(&mut contract, vec![token_a(), token_b()]);
+    create_pool(&mut contract, vec![token_a(), token_c()]);
+    create_pool(&mut contract, vec![token_a(), token_b(), token_c()]);
+
+    contract
+}
+
+#[test]
+fn test_pools_are_indexed_on_bind() {
+    let contract = create_pools();
+
+    assert_eq!(contract.get_pools_for_token(&token_a(), U64(0), U64(10)), vec![U64(1), U64(2), U64(3)]);
+    assert_eq!(contract.get_pools_for_token(&token_b(), U64(0), U64(10)), vec![U64(1), U64(3)]);
+    assert_eq!(contract.get_pools_for_pair(&token_a(), &token_b(), U64(0), U64(10)), vec![U64(1), U64(3)]);
+    assert_eq!(contract.get_pools_for_pair(&token_b(), &token_a(), U64(0), U64(10)), vec![U64(1), U64(3)]);
+    assert_eq!(contract.get_pools_for_pair(&token_b(), &token_c(), U64(0), U64(10)), vec![U64(3)]);
+    assert_eq!(contract.get_pools_for_token(&bob(), U64(0), U64(10)), vec![]);
+}
+
+#[test]
+fn test_pool_index_pagination() {
+    let contract = create_pools();
+
+    assert_eq!(contract.get_pools_for_token(&token_a(), U64(1), U64(1)), vec![U64(2)]);
+    assert_eq!(contract.get_pools_for_token(&token_a(), U64(2), U64(10)), vec![U64(3)]);
+    assert_eq!(contract.get_pools_for_token(&token_a(), U64(3), U64(10)), vec![]);
+}
+
+#[test]
+fn test_pools_are_unindexed_on_unbind() {
+    let mut contract = create_pools();
+    contract.unbind_pool(U64(3), &token_a());
+
+    assert_eq!(contract.get_pools_for_token(&token_a(), U64(0), U64(10)), vec![U64(1), U64(2)]);
+    assert_eq!(contract.get_pools_for_pair(&token_a(), &token_b(), U64(0), U64(10)), vec![U64(1)]);
+    assert_eq!(contract.get_pools_for_pair(&token_a(), &token_c(), U64(0), U64(10)), vec![U64(2)]);
+    assert_eq!(contract.get_pools_for_pair(&token_b(), &token_c(), U64(0), U64(10)), vec![U64(3)]);
+}
+
+#[test]
+fn test_unbind_updates_moved_token_index() {
+    let mut contract = create_pools();
+
+    // Token c takes the place of token a, it has to be unbindable from there
+    contract.unbind_pool(U64(3), &token_a());
+    assert_eq!(contract.get_pool_current_tokens(U64(3)), vec![token_c(), token_b()]);
+    contract.unbind_pool(U64(3), &token_c());
+    assert_eq!(contract.get_pool_current_tokens(U64(3)), vec![token_b()]);
+}
+
+#[test]
+fn test_finalized_pools_stay_indexed() {
+    let mut contract = create_pools();
+    contract.unbind_pool(U64(3), &token_a());
+    contract.finalize_pool(U64(3));
+
+    // Finalizing indexes the final set of tokens, the unbound token isn't added back
+    assert_eq!(contract.get_pools_for_token(&token_a(), U64(0), U64(10)), vec![U64(1), U64(2)]);
+    assert_eq!(contract.get_pools_for_token(&token_c(), U64(0), U64(10)), vec![U64(2), U64(3)]);
+    assert_eq!(contract.get_pools_for_pair(&token_b(), &token_c(), U64(0), U64(10)), vec![U64(3)]);
+    assert_eq!(contract.get_pools_for_pair(&token_a(), &token_c(), U64(0), U64(10)), vec![U64(2)]);
+}
+
+#[test]
+fn test_outcome_pools_are_indexed() {
+    let mut contract = create_pools();
+    let pool_id = contract.new_outcome_pool(
+        token_a(),
+        vec![token_b(), token_c()],
+        vec![U128(to_token_denom(1) / 2), U128(to_token_denom(1) / 2)],
+        swap_fee(),
+        U128(to_token_denom(100))
+    );
+
+    // Outcome pools are only indexed once they're finalized
+    assert_eq!(contract.get_pools_for_pair(&token_b(), &token_c(), U64(0), U64(10)), vec![U64(3)]);
+
+    testing_env_with_promise_results(get_context(alice(), 0), promise_results(&[true, true, true]));
+    contract.resolve_outcome_pool(pool_id, alice(), vec![token_a(), token_b(), token_c()]);
+
+    assert_eq!(contract.get_pools_for_pair(&token_b(), &token_c(), U64(0), U64(10)), vec![U64(3), pool_id]);
+    assert_eq!(contract.get_pools_for_pair(&token_a(), &token_b(), U64(0), U64(10)), vec![U64(1), U64(3), pool_id]);
+}
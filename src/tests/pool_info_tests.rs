@@ -0,0 +1,76 @@
+use super::*;
+use crate::pool::{
+    PoolInfo,
+    PoolTokenInfo
+};
+
+fn create_pools() -> PoolFactory {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    let pool_id = contract.new_pool(swap_fee());
+    bind_pool(&mut contract, pool_id, &token_a(), U128(to_token_denom(10)), U128(to_token_denom(100)));
+    bind_pool(&mut contract, pool_id, &token_b(), U128(to_token_denom(30)), U128(to_token_denom(50)));
+    contract.finalize_pool(pool_id);
+
+    testing_env!(get_context(bob(), 0));
+    let pool_id = contract.new_pool(swap_fee());
+    bind_pool(&mut contract, pool_id, &token_c(), U128(to_token_denom(10)), U128(to_token_denom(10)));
+
+    contract
+}
+
+fn pool_1_info() -> PoolInfo {
+    PoolInfo {
+        id: U64(1),
+        controller: alice(),
+        finalized: true,
+        swap_fee: swap_fee(),
+        total_weight: U128(to_token_denom(40)),
+        total_supply: U128(to_token_denom(100)),
+        tokens: vec![
+            PoolTokenInfo {
+                token: token_a(),
+                denorm: U128(to_token_denom(10)),
+                normalized_weight: U128(to_token_denom(1) / 4),
+                balance: U128(to_token_denom(100))
+            },
+            PoolTokenInfo {
+                token: token_b(),
+                denorm: U128(to_token_denom(30)),
+                normalized_weight: U128(to_token_denom(3) / 4),
+                balance: U128(to_token_denom(50))
+            }
+        ]
+    }
+}
+
+#[test]
+fn test_get_pool_info() {
+    let contract = create_pools();
+    assert_eq!(contract.get_pool_info(U64(1)), pool_1_info());
+}
+
+#[test]
+fn test_get_pools() {
+    let contract = create_pools();
+
+    let pools = contract.get_pools(U64(0), U64(10));
+    assert_eq!(pools.len(), 2);
+    assert_eq!(pools[0], pool_1_info());
+    assert_eq!(pools[1].controller, bob());
+    assert!(!pools[1].finalized);
+    assert_eq!(pools[1].total_supply, U128(0));
+
+    let pools = contract.get_pools(U64(1), U64(1));
+    assert_eq!(pools.len(), 1);
+    assert_eq!(pools[0].id, U64(2));
+}
+
+#[test]
+#[should_panic(expected = "ERR_NO_POOL")]
+fn test_get_pool_info_no_pool() {
+    let contract = create_pools();
+    contract.get_pool_info(U64(3));
+}
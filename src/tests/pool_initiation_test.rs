@@ -25,14 +25,14 @@ fn bind_and_finalize_valid_pool() {
     
     let pool_id = contract.new_pool(swap_fee());
 
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_a(),
         U128(to_token_denom(5)),
         U128(to_token_denom(1000))
     );
 
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_b(),
         U128(to_token_denom(5)),
@@ -54,7 +54,7 @@ fn bind_rebind_finalize_valid_pool() {
     let pool_id = contract.new_pool(swap_fee());
 
     // Bind token_a
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_a(),
         U128(to_token_denom(5)),
@@ -62,7 +62,7 @@ fn bind_rebind_finalize_valid_pool() {
     );
 
     // Bind token_b
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_b(),
         U128(to_token_denom(5)),
@@ -0,0 +1,116 @@
+use super::*;
+use crate::constants::{
+    INIT_POOL_SUPPLY
+};
+
+fn create_even_pool() -> (PoolFactory, U64) {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+    
+    let pool_id = contract.new_pool(swap_fee());
+
+    bind_pool(&mut contract, 
+        pool_id,
+        &"dai.near".to_string(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(100))
+    );
+
+    bind_pool(&mut contract, 
+        pool_id,
+        &"mkr.near".to_string(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(1))
+    );
+    contract.finalize_pool(pool_id);
+
+    (contract, pool_id)
+}
+
+// Pool token transfers need exactly one yoctoNEAR attached
+fn transfer_context(predecessor_account_id: AccountId) -> VMContext {
+    let mut context = get_context(predecessor_account_id, 0);
+    context.attached_deposit = 1;
+    context
+}
+
+#[test]
+fn test_pool_token_transfer() {
+    let  (mut contract, pool_id) = create_even_pool();
+    let amount = to_token_denom(40);
+
+    testing_env!(transfer_context(alice()));
+    contract.transfer(pool_id, bob(), U128(amount));
+
+    assert_eq!(contract.ft_balance_of(pool_id, alice()), U128(INIT_POOL_SUPPLY - amount));
+    assert_eq!(contract.ft_balance_of(pool_id, bob()), U128(amount));
+    assert_eq!(contract.ft_total_supply(pool_id), U128(INIT_POOL_SUPPLY));
+
+    // Bob can move the received tokens on
+    testing_env!(transfer_context(bob()));
+    contract.transfer(pool_id, carol(), U128(amount));
+
+    assert_eq!(contract.ft_balance_of(pool_id, bob()), U128(0));
+    assert_eq!(contract.ft_balance_of(pool_id, carol()), U128(amount));
+}
+
+#[test]
+#[should_panic(expected = "Not enough balance")]
+fn test_pool_token_transfer_insufficient_balance() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    testing_env!(transfer_context(bob()));
+    contract.transfer(pool_id, carol(), U128(1));
+}
+
+#[test]
+#[should_panic(expected = "ERR_SELF_TRANSFER")]
+fn test_pool_token_self_transfer() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    testing_env!(transfer_context(alice()));
+    contract.transfer(pool_id, alice(), U128(1));
+}
+
+#[test]
+#[should_panic(expected = "ERR_ONE_YOCTO")]
+fn test_pool_token_transfer_without_deposit() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    contract.transfer(pool_id, bob(), U128(1));
+}
+
+#[test]
+fn test_pool_token_transfer_call_debits_sender() {
+    let  (mut contract, pool_id) = create_even_pool();
+    let amount = to_token_denom(10);
+
+    testing_env!(transfer_context(alice()));
+    contract.transfer_call(pool_id, bob(), U128(amount), "stake".to_string());
+
+    // Unused tokens are only refunded once the receiver has responded
+    assert_eq!(contract.ft_balance_of(pool_id, alice()), U128(INIT_POOL_SUPPLY - amount));
+    assert_eq!(contract.ft_balance_of(pool_id, bob()), U128(amount));
+}
+
+#[test]
+#[should_panic(expected = "ERR_ONE_YOCTO")]
+fn test_pool_token_transfer_call_with_more_than_one_yocto() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    let mut context = transfer_context(alice());
+    context.attached_deposit = 2;
+    testing_env!(context);
+    contract.transfer_call(pool_id, bob(), U128(1), "stake".to_string());
+}
+
+#[test]
+fn test_pool_token_metadata() {
+    let  (contract, pool_id) = create_even_pool();
+
+    let metadata = contract.ft_metadata(pool_id);
+    assert_eq!(metadata.name, "Flux AMM DAI/MKR #1");
+    assert_eq!(metadata.symbol, "DAI-MKR");
+    assert_eq!(metadata.decimals, 18);
+}
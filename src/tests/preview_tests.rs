@@ -0,0 +1,134 @@
+use super::*;
+use near_sdk::serde_json;
+use crate::pool::{
+    PoolError,
+    PoolResult,
+    SwapPreview
+};
+
+fn create_pool() -> (PoolFactory, U64) {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    let pool_id = contract.new_pool(swap_fee());
+
+    bind_pool(&mut contract, 
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(100))
+    );
+
+    bind_pool(&mut contract, 
+        pool_id,
+        &token_b(),
+        U128(to_token_denom(20)),
+        U128(to_token_denom(50))
+    );
+    contract.finalize_pool(pool_id);
+
+    (contract, pool_id)
+}
+
+#[test]
+fn test_preview_swap_exact_amount_in() {
+    let (mut contract, pool_id) = create_pool();
+    let preview = contract.get_pool_out_given_in(pool_id, &token_a(), U128(to_token_denom(10)), &token_b()).unwrap();
+
+    testing_env!(get_context(bob(), 0));
+    let (amount_out, spot_price_after) = contract.swap_exact_amount_in(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        &token_b(),
+        U128(0),
+        U128(to_token_denom(1000))
+    );
+
+    assert_eq!(preview.token_amount_in, U128(to_token_denom(10)));
+    assert_eq!(preview.token_amount_out, amount_out);
+    assert_eq!(preview.spot_price_after, spot_price_after);
+}
+
+#[test]
+fn test_preview_swap_exact_amount_out() {
+    let (mut contract, pool_id) = create_pool();
+    let preview = contract.get_pool_in_given_out(pool_id, &token_a(), &token_b(), U128(to_token_denom(5))).unwrap();
+
+    testing_env!(get_context(bob(), 0));
+    let (amount_in, spot_price_after) = contract.swap_exact_amount_out(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(1000)),
+        &token_b(),
+        U128(to_token_denom(5)),
+        U128(to_token_denom(1000))
+    );
+
+    assert_eq!(preview.token_amount_in, amount_in);
+    assert_eq!(preview.token_amount_out, U128(to_token_denom(5)));
+    assert_eq!(preview.spot_price_after, spot_price_after);
+}
+
+#[test]
+fn test_preview_join_and_exit_pool() {
+    let (mut contract, pool_id) = create_pool();
+
+    let amounts_in = contract.preview_join_pool(pool_id, U128(to_token_denom(10))).unwrap();
+    assert_eq!(amounts_in, vec![U128(to_token_denom(10)), U128(to_token_denom(5))]);
+
+    testing_env!(get_context(bob(), 0));
+    contract.join_pool(pool_id, U128(to_token_denom(10)), amounts_in.clone());
+    resolve_join(&mut contract, pool_id, bob(), vec![(token_a(), amounts_in[0].into()), (token_b(), amounts_in[1].into())], to_token_denom(10));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(110)));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_b()), U128(to_token_denom(55)));
+
+    let amounts_out = contract.preview_exit_pool(pool_id, U128(to_token_denom(10))).unwrap();
+    contract.exit_pool(pool_id, U128(to_token_denom(10)), amounts_out.clone());
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(110) - u128::from(amounts_out[0])));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_b()), U128(to_token_denom(55) - u128::from(amounts_out[1])));
+}
+
+#[test]
+fn test_preview_errors() {
+    let (contract, pool_id) = create_pool();
+
+    assert_eq!(contract.get_pool_out_given_in(pool_id, &token_a(), U128(to_token_denom(60)), &token_b()), Err(PoolError::MaxInRatio));
+    assert_eq!(contract.get_pool_out_given_in(pool_id, &token_c(), U128(to_token_denom(1)), &token_b()), Err(PoolError::NotBound));
+    assert_eq!(contract.get_pool_in_given_out(pool_id, &token_a(), &token_b(), U128(to_token_denom(20))), Err(PoolError::MaxOutRatio));
+    assert_eq!(contract.preview_exit_pool(pool_id, U128(0)), Err(PoolError::MathApprox));
+    assert_eq!(contract.preview_join_pool(U64(2), U128(to_token_denom(1))), Err(PoolError::NoPool));
+
+    // Amounts that pay out nothing are rejected before the effective price divides by them
+    assert_eq!(contract.get_pool_out_given_in(pool_id, &token_a(), U128(0), &token_b()), Err(PoolError::ZeroAmount));
+    assert_eq!(contract.get_pool_out_given_in(pool_id, &token_a(), U128(1), &token_b()), Err(PoolError::ZeroAmount));
+    assert_eq!(contract.get_pool_in_given_out(pool_id, &token_a(), &token_b(), U128(0)), Err(PoolError::ZeroAmount));
+}
+
+#[test]
+#[should_panic(expected = "ERR_ZERO_AMOUNT")]
+fn test_swap_paying_out_nothing_fails() {
+    let (mut contract, pool_id) = create_pool();
+
+    testing_env!(get_context(bob(), 0));
+    contract.swap_exact_amount_in(pool_id, &token_a(), U128(1), &token_b(), U128(0), U128(u128::MAX));
+}
+
+#[test]
+fn test_preview_error_json() {
+    let error: PoolResult<SwapPreview> = Err(PoolError::LimitOut);
+    assert_eq!(serde_json::to_string(&error).unwrap(), r#"{"Err":"ERR_LIMIT_OUT"}"#);
+}
+
+#[test]
+#[should_panic(expected = "ERR_NOT_FINALIZED")]
+fn test_join_pool_panics_with_preview_error() {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+    let pool_id = contract.new_pool(swap_fee());
+
+    assert_eq!(contract.preview_join_pool(pool_id, U128(to_token_denom(1))), Err(PoolError::NotFinalized));
+    contract.join_pool(pool_id, U128(to_token_denom(1)), vec![]);
+}
@@ -9,14 +9,14 @@ fn test_finalized_even_pool_pricing_sans_fee() {
     
     let pool_id = contract.new_pool(swap_fee());
 
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_a(),
         U128(to_token_denom(10)),
         U128(to_token_denom(1000))
     );
 
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_b(),
         U128(to_token_denom(10)),
@@ -40,7 +40,7 @@ fn test_finalized_uneven_pool_pricing_sans_fee() {
     let pool_id = contract.new_pool(swap_fee());
 
     // Token a is stable coin worth $1
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_a(),
         U128(to_token_denom(20)),
@@ -48,7 +48,7 @@ fn test_finalized_uneven_pool_pricing_sans_fee() {
     );
 
     // Token b is governance token worth $200
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_b(),
         U128(to_token_denom(10)),
@@ -74,7 +74,7 @@ fn test_finalized_uneven_pool_pricing() {
     let pool_id = contract.new_pool(swap_fee());
 
     // Token a is stable coin worth $1
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_a(),
         U128(to_token_denom(20)),
@@ -82,7 +82,7 @@ fn test_finalized_uneven_pool_pricing() {
     );
 
     // Token b is governance token worth $200
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_b(),
         U128(to_token_denom(10)),
@@ -110,7 +110,7 @@ fn create_uneven_pool() -> (PoolFactory, U64) {
     let pool_id = contract.new_pool(swap_fee());
 
     // Token a is stable coin worth $1
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_a(),
         U128(to_token_denom(20)),
@@ -118,7 +118,7 @@ fn create_uneven_pool() -> (PoolFactory, U64) {
     );
 
     // Token b is governance token worth $200
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_b(),
         U128(to_token_denom(10)),
@@ -126,7 +126,7 @@ fn create_uneven_pool() -> (PoolFactory, U64) {
     );
     
     // Token b is governance token worth $200
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_c(),
         U128(to_token_denom(10)),
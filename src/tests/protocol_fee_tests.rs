@@ -0,0 +1,185 @@
+use super::*;
+use crate::constants::MAX_PROTOCOL_FEE;
+
+fn create_pool(protocol_fee: u128) -> (PoolFactory, U64) {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+    contract.set_protocol_fee(U128(protocol_fee));
+
+    let pool_id = contract.new_pool(swap_fee());
+
+    bind_pool(&mut contract, 
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(100))
+    );
+
+    bind_pool(&mut contract, 
+        pool_id,
+        &token_b(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(100))
+    );
+    contract.finalize_pool(pool_id);
+
+    (contract, pool_id)
+}
+
+// 20% of the 0.003 swap fee on the 10 tokens in of `swap_a_for_b`
+const PROTOCOL_FEE_AMOUNT: u128 = 6_000_000_000_000_000;
+
+fn swap_a_for_b(contract: &mut PoolFactory, pool_id: U64) -> U128 {
+    testing_env!(get_context(bob(), 0));
+    let (amount_out, _) = contract.swap_exact_amount_in(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        &token_b(),
+        U128(0),
+        U128(to_token_denom(1000))
+    );
+    amount_out
+}
+
+// Stands in for the callback of the swap's pull of token a
+fn resolve_swap(contract: &mut PoolFactory, pool_id: U64, amount_out: U128, success: bool) {
+    let protocol_fee_amount = match u128::from(contract.get_protocol_fee()) {
+        0 => 0,
+        _ => PROTOCOL_FEE_AMOUNT
+    };
+    testing_env_with_promise_results(get_context(alice(), 0), promise_results(&[success]));
+    contract.resolve_swap(pool_id, bob(), token_a(), U128(to_token_denom(10)), token_b(), amount_out, U128(protocol_fee_amount));
+    testing_env!(get_context(alice(), 0));
+}
+
+fn collect_protocol_fees(contract: &mut PoolFactory, success: bool) {
+    testing_env!(get_context(alice(), 0));
+    contract.collect_protocol_fees(vec![token_a()]);
+    assert_eq!(contract.get_accrued_protocol_fees(&token_a()), U128(0));
+
+    testing_env_with_promise_results(get_context(alice(), 0), promise_results(&[success]));
+    contract.resolve_collect_protocol_fee(token_a(), U128(PROTOCOL_FEE_AMOUNT));
+    testing_env!(get_context(alice(), 0));
+}
+
+// Each call resets the mocked blockchain, the results have to be read before the next pool is created
+fn swap_and_exit_half(contract: &mut PoolFactory, pool_id: U64) -> (u128, u128, u128) {
+    let amount_out = swap_a_for_b(contract, pool_id);
+    resolve_swap(contract, pool_id, amount_out, true);
+
+    let pool_a_balance: u128 = contract.get_pool_balance(pool_id, &token_a()).into();
+    contract.exit_pool(pool_id, U128(to_token_denom(50)), vec![U128(0), U128(0)]);
+    let pool_a_balance_after: u128 = contract.get_pool_balance(pool_id, &token_a()).into();
+
+    (amount_out.into(), pool_a_balance - pool_a_balance_after, pool_a_balance_after)
+}
+
+#[test]
+fn test_set_protocol_fee() {
+    let (contract, _pool_id) = create_pool(to_token_denom(1) / 5);
+    assert_eq!(contract.get_protocol_fee(), U128(to_token_denom(1) / 5));
+}
+
+#[test]
+#[should_panic(expected = "ERR_NOT_OWNER")]
+fn test_set_protocol_fee_not_owner() {
+    let (mut contract, _pool_id) = create_pool(0);
+    testing_env!(get_context(bob(), 0));
+    contract.set_protocol_fee(U128(to_token_denom(1) / 5));
+}
+
+#[test]
+#[should_panic(expected = "ERR_MAX_PROTOCOL_FEE")]
+fn test_set_protocol_fee_above_max() {
+    create_pool(MAX_PROTOCOL_FEE + 1);
+}
+
+#[test]
+#[should_panic(expected = "ERR_NOT_OWNER")]
+fn test_collect_protocol_fees_not_owner() {
+    let (mut contract, _pool_id) = create_pool(0);
+    testing_env!(get_context(bob(), 0));
+    contract.collect_protocol_fees(vec![token_a()]);
+}
+
+#[test]
+fn test_protocol_fee_reduces_lp_returns() {
+    let (mut contract, pool_id) = create_pool(0);
+    let (amount_out, lp_amount_out, pool_a_balance) = swap_and_exit_half(&mut contract, pool_id);
+
+    let (mut contract_with_fee, pool_id) = create_pool(to_token_denom(1) / 5);
+    let (amount_out_with_fee, lp_amount_out_with_fee, pool_a_balance_with_fee) = swap_and_exit_half(&mut contract_with_fee, pool_id);
+
+    // Traders pay the same, the protocol fee is withheld from the pool
+    assert_eq!(amount_out, amount_out_with_fee);
+    assert_eq!(pool_a_balance - pool_a_balance_with_fee, PROTOCOL_FEE_AMOUNT / 2);
+
+    // Exiting half of the pool tokens forfeits exactly half of the protocol share
+    assert_eq!(lp_amount_out, to_token_denom(55));
+    assert_eq!(lp_amount_out - lp_amount_out_with_fee, PROTOCOL_FEE_AMOUNT / 2);
+    assert_eq!(contract_with_fee.get_accrued_protocol_fees(&token_a()), U128(PROTOCOL_FEE_AMOUNT));
+}
+
+#[test]
+fn test_resolved_swap_accrues_protocol_fee() {
+    let (mut contract, pool_id) = create_pool(to_token_denom(1) / 5);
+    let amount_out = swap_a_for_b(&mut contract, pool_id);
+
+    // Withheld fees only accrue once the transfer in has resolved
+    assert_eq!(contract.get_accrued_protocol_fees(&token_a()), U128(0));
+
+    resolve_swap(&mut contract, pool_id, amount_out, true);
+    assert_eq!(contract.get_accrued_protocol_fees(&token_a()), U128(PROTOCOL_FEE_AMOUNT));
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(110)));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(110) - PROTOCOL_FEE_AMOUNT));
+    assert!(!contract.pool_is_locked(pool_id));
+}
+
+#[test]
+fn test_failed_swap_accrues_no_protocol_fee() {
+    let (mut contract, pool_id) = create_pool(to_token_denom(1) / 5);
+    let amount_out = swap_a_for_b(&mut contract, pool_id);
+
+    resolve_swap(&mut contract, pool_id, amount_out, false);
+    assert_eq!(contract.get_accrued_protocol_fees(&token_a()), U128(0));
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(100)));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(100)));
+}
+
+#[test]
+fn test_collect_protocol_fees() {
+    let (mut contract, pool_id) = create_pool(to_token_denom(1) / 5);
+    let amount_out = swap_a_for_b(&mut contract, pool_id);
+    resolve_swap(&mut contract, pool_id, amount_out, true);
+
+    // The fees leave custody once the transfer to the owner resolved, the pool's balance is untouched
+    collect_protocol_fees(&mut contract, true);
+    assert_eq!(contract.get_accrued_protocol_fees(&token_a()), U128(0));
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(110) - PROTOCOL_FEE_AMOUNT));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(110) - PROTOCOL_FEE_AMOUNT));
+
+    // Nothing is left to collect
+    contract.collect_protocol_fees(vec![token_a()]);
+    assert_eq!(contract.get_accrued_protocol_fees(&token_a()), U128(0));
+}
+
+#[test]
+fn test_failed_protocol_fee_payout_is_accrued_again() {
+    let (mut contract, pool_id) = create_pool(to_token_denom(1) / 5);
+    let amount_out = swap_a_for_b(&mut contract, pool_id);
+    resolve_swap(&mut contract, pool_id, amount_out, true);
+
+    collect_protocol_fees(&mut contract, false);
+    assert_eq!(contract.get_accrued_protocol_fees(&token_a()), U128(PROTOCOL_FEE_AMOUNT));
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(110)));
+}
+
+#[test]
+#[should_panic(expected = "ERR_PRIVATE_METHOD")]
+fn test_resolve_collect_protocol_fee_is_private() {
+    let (mut contract, _pool_id) = create_pool(to_token_denom(1) / 5);
+    testing_env!(get_context(bob(), 0));
+    contract.resolve_collect_protocol_fee(token_a(), U128(PROTOCOL_FEE_AMOUNT));
+}
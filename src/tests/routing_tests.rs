@@ -0,0 +1,131 @@
+use super::*;
+use crate::pool_factory::RouteAmount;
+
+fn create_pool(contract: &mut PoolFactory, token_in: AccountId, token_out: AccountId, balance: u128) -> U64 {
+    let pool_id = contract.new_pool(swap_fee());
+
+    for token in &[token_in, token_out] {
+        bind_pool(contract, 
+            pool_id,
+            token,
+            U128(to_token_denom(10)),
+            U128(balance)
+        );
+    }
+    contract.finalize_pool(pool_id);
+
+    pool_id
+}
+
+// Pool 1 and 2 both trade token a for b, pool 3 trades token b for c
+fn create_pools() -> PoolFactory {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    create_pool(&mut contract, token_a(), token_b(), to_token_denom(100));
+    create_pool(&mut contract, token_a(), token_b(), to_token_denom(50));
+    create_pool(&mut contract, token_b(), token_c(), to_token_denom(100));
+
+    testing_env!(get_context(bob(), 0));
+    contract
+}
+
+#[test]
+fn test_quote_splits_over_pools_with_the_same_pair() {
+    let contract = create_pools();
+    let quote = contract.quote_best_route(token_a(), token_b(), U128(to_token_denom(10)), U64(1));
+
+    assert_eq!(quote.routes.len(), 2);
+    let total_in: u128 = quote.routes.iter().map(|route| u128::from(route.token_amount_in)).sum();
+    let total_out: u128 = quote.routes.iter().map(|route| u128::from(route.token_amount_out)).sum();
+    assert_eq!(total_in, to_token_denom(10));
+    assert_eq!(total_out, u128::from(quote.total_amount_out));
+
+    // The deeper pool takes 7 of the 10 parts
+    assert_eq!(quote.routes[0].route[0].pool_id, U64(1));
+    assert_eq!(quote.routes[0].token_amount_in, U128(to_token_denom(7)));
+    assert_eq!(quote.routes[1].route[0].pool_id, U64(2));
+    assert_eq!(quote.routes[1].token_amount_in, U128(to_token_denom(3)));
+    assert_eq!(quote.total_amount_out, U128(9345887611763227300));
+
+    // Swapping all of it in the deeper pool would have paid out 9066108938801491300
+    assert!(u128::from(quote.total_amount_out) > 9066108938801491300);
+}
+
+#[test]
+fn test_quote_multi_hop() {
+    let contract = create_pools();
+    let quote = contract.quote_best_route(token_a(), token_c(), U128(to_token_denom(10)), U64(2));
+
+    // Both routes end in pool 3 so only one of them can be used
+    assert_eq!(quote.routes.len(), 1);
+    let route = &quote.routes[0].route;
+    assert_eq!(route.len(), 2);
+    assert_eq!(route[0].pool_id, U64(1));
+    assert_eq!(route[1].pool_id, U64(3));
+    assert_eq!(quote.routes[0].token_amount_in, U128(to_token_denom(10)));
+}
+
+#[test]
+fn test_quote_amount_smaller_than_parts() {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+    create_pool(&mut contract, token_a(), token_b(), 1_000_000);
+
+    // Each unit is a part of its own instead of quoting parts of 0
+    let quote = contract.quote_best_route(token_a(), token_b(), U128(5), U64(1));
+    assert_eq!(quote.routes.len(), 1);
+    assert_eq!(quote.routes[0].token_amount_in, U128(5));
+    assert!(u128::from(quote.total_amount_out) > 0);
+}
+
+#[test]
+fn test_split_swap_exact_in_executes_quote() {
+    let mut contract = create_pools();
+    let quote = contract.quote_best_route(token_a(), token_b(), U128(to_token_denom(10)), U64(1));
+
+    let splits = quote.routes.iter().map(|route| RouteAmount {
+        route: route.route.clone(),
+        token_amount_in: route.token_amount_in
+    }).collect();
+    let total_out = contract.split_swap_exact_in(splits, quote.total_amount_out);
+
+    assert_eq!(total_out, quote.total_amount_out);
+    for route in &quote.routes {
+        let pool_id = route.route[0].pool_id;
+        let initial_balance = if pool_id == U64(1) { to_token_denom(100) } else { to_token_denom(50) };
+        assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(initial_balance + u128::from(route.token_amount_in)));
+        assert_eq!(contract.get_pool_balance(pool_id, &token_b()), U128(initial_balance - u128::from(route.token_amount_out)));
+    }
+}
+
+#[test]
+#[should_panic(expected = "ERR_LIMIT_OUT")]
+fn test_split_swap_exact_in_limit_out() {
+    let mut contract = create_pools();
+    let quote = contract.quote_best_route(token_a(), token_b(), U128(to_token_denom(10)), U64(1));
+
+    let splits = quote.routes.iter().map(|route| RouteAmount {
+        route: route.route.clone(),
+        token_amount_in: route.token_amount_in
+    }).collect();
+    contract.split_swap_exact_in(splits, U128(u128::from(quote.total_amount_out) + 1));
+}
+
+#[test]
+fn test_quote_no_route() {
+    let contract = create_pools();
+    let quote = contract.quote_best_route(token_a(), token_c(), U128(to_token_denom(10)), U64(1));
+
+    assert!(quote.routes.is_empty());
+    assert_eq!(quote.total_amount_out, U128(0));
+}
+
+#[test]
+#[should_panic(expected = "ERR_MAX_HOPS")]
+fn test_quote_max_hops() {
+    let contract = create_pools();
+    contract.quote_best_route(token_a(), token_c(), U128(to_token_denom(10)), U64(4));
+}
@@ -0,0 +1,331 @@
+use near_crypto::{
+    InMemorySigner,
+    KeyType,
+    Signer
+};
+use near_primitives::{
+    account::AccessKey,
+    hash::CryptoHash,
+    transaction::{
+        ExecutionOutcome,
+        Transaction
+    },
+    types::Balance
+};
+use near_runtime_standalone::{
+    init_runtime_and_signer,
+    RuntimeStandalone
+};
+use near_sdk::{
+    serde_json::{
+        self,
+        json,
+        Value
+    }
+};
+
+/**
+ * Simulation tests run the compiled contracts in a standalone runtime so real token balances can be checked.
+ * `res/flux_amm.wasm` is produced by `scripts/build.sh`, `res/mock_token.wasm` by `scripts/build_mock_token.sh`
+ * from `mock_token/`, a NEP-21 token (`new`, `inc_allowance`, `transfer`, `transfer_from`, `get_balance`)
+ * that doesn't charge storage deposits.
+ */
+
+const MAX_GAS: u64 = 300_000_000_000_000;
+const STORAGE_AMOUNT: Balance = 50_000_000_000_000_000_000_000_000;
+
+lazy_static::lazy_static! {
+    static ref AMM_WASM_BYTES: Vec<u8> = std::fs::read("res/flux_amm.wasm").expect("ERR_NO_AMM_WASM");
+    static ref TOKEN_WASM_BYTES: Vec<u8> = std::fs::read("res/mock_token.wasm").expect("ERR_NO_TOKEN_WASM");
+}
+
+fn root() -> String {
+    "near".to_string()
+}
+
+fn amm() -> String {
+    "amm.near".to_string()
+}
+
+fn sim_alice() -> String {
+    "alice.near".to_string()
+}
+
+fn sim_bob() -> String {
+    "bob.near".to_string()
+}
+
+fn sim_carol() -> String {
+    "carol.near".to_string()
+}
+
+fn sim_token_a() -> String {
+    "token_a.near".to_string()
+}
+
+fn sim_token_b() -> String {
+    "token_b.near".to_string()
+}
+
+struct ExternalUser {
+    account_id: String,
+    signer: InMemorySigner
+}
+
+impl ExternalUser {
+    fn new_tx(&self, runtime: &RuntimeStandalone, receiver_id: &str) -> Transaction {
+        let nonce = runtime
+            .view_access_key(&self.account_id, &self.signer.public_key())
+            .expect("ERR_NO_ACCESS_KEY")
+            .nonce + 1;
+
+        Transaction::new(
+            self.account_id.to_string(),
+            self.signer.public_key(),
+            receiver_id.to_string(),
+            nonce,
+            CryptoHash::default()
+        )
+    }
+
+    fn create_external(&self, runtime: &mut RuntimeStandalone, new_account_id: &str) -> ExternalUser {
+        let signer = InMemorySigner::from_seed(new_account_id, KeyType::ED25519, new_account_id);
+        let tx = self.new_tx(runtime, new_account_id)
+            .create_account()
+            .add_key(signer.public_key(), AccessKey::full_access())
+            .transfer(STORAGE_AMOUNT)
+            .sign(&self.signer);
+        runtime.resolve_tx(tx).expect("ERR_CREATE_ACCOUNT");
+        runtime.process_all().expect("ERR_PROCESS");
+
+        ExternalUser {
+            account_id: new_account_id.to_string(),
+            signer
+        }
+    }
+
+    fn deploy(&self, runtime: &mut RuntimeStandalone, account_id: &str, code: Vec<u8>, init_method: &str, init_args: Value) {
+        let signer = InMemorySigner::from_seed(account_id, KeyType::ED25519, account_id);
+        let tx = self.new_tx(runtime, account_id)
+            .create_account()
+            .add_key(signer.public_key(), AccessKey::full_access())
+            .transfer(STORAGE_AMOUNT)
+            .deploy_contract(code)
+            .function_call(init_method.to_string(), init_args.to_string().into_bytes(), MAX_GAS, 0)
+            .sign(&self.signer);
+        runtime.resolve_tx(tx).expect("ERR_DEPLOY");
+        runtime.process_all().expect("ERR_PROCESS");
+    }
+
+    fn call(&self, runtime: &mut RuntimeStandalone, receiver_id: &str, method: &str, args: Value) -> ExecutionOutcome {
+        let tx = self.new_tx(runtime, receiver_id)
+            .function_call(method.to_string(), args.to_string().into_bytes(), MAX_GAS, 0)
+            .sign(&self.signer);
+        let outcome = runtime.resolve_tx(tx).expect("ERR_CALL");
+        runtime.process_all().expect("ERR_PROCESS");
+        outcome
+    }
+}
+
+fn view(runtime: &RuntimeStandalone, account_id: &str, method: &str, args: Value) -> Value {
+    let (result, _logs) = runtime
+        .view_method_call(account_id, method, args.to_string().as_bytes())
+        .expect("ERR_VIEW");
+    serde_json::from_slice(&result).expect("ERR_DESERIALIZE")
+}
+
+fn view_u128(runtime: &RuntimeStandalone, account_id: &str, method: &str, args: Value) -> u128 {
+    view(runtime, account_id, method, args)
+        .as_str()
+        .expect("ERR_NOT_A_STRING")
+        .parse()
+        .expect("ERR_NOT_A_NUMBER")
+}
+
+fn token_balance(runtime: &RuntimeStandalone, token_account_id: &str, owner_id: &str) -> u128 {
+    view_u128(runtime, token_account_id, "get_balance", json!({"owner_id": owner_id}))
+}
+
+fn pool_balance(runtime: &RuntimeStandalone, pool_id: u64, token_account_id: &str) -> u128 {
+    view_u128(runtime, &amm(), "get_pool_balance", json!({"pool_id": pool_id.to_string(), "token_account_id": token_account_id}))
+}
+
+fn to_token_denom(amt: u128) -> u128 {
+    amt * 10_u128.pow(18)
+}
+
+/**
+ * @notice deploys both tokens and the amm, alice owns the token supply and has created and funded pool 1
+ */
+fn init_pool() -> (RuntimeStandalone, ExternalUser, ExternalUser, ExternalUser) {
+    let (mut runtime, signer) = init_runtime_and_signer(&root());
+    let root = ExternalUser { account_id: root(), signer };
+
+    for token in &[sim_token_a(), sim_token_b()] {
+        root.deploy(
+            &mut runtime,
+            token,
+            TOKEN_WASM_BYTES.to_vec(),
+            "new",
+            json!({"owner_id": sim_alice(), "total_supply": to_token_denom(1000).to_string()})
+        );
+    }
+    root.deploy(&mut runtime, &amm(), AMM_WASM_BYTES.to_vec(), "init", json!({"owner": sim_alice()}));
+
+    let alice = root.create_external(&mut runtime, &sim_alice());
+    let bob = root.create_external(&mut runtime, &sim_bob());
+    let carol = root.create_external(&mut runtime, &sim_carol());
+
+    for token in &[sim_token_a(), sim_token_b()] {
+        alice.call(&mut runtime, token, "inc_allowance", json!({"escrow_account_id": amm(), "amount": to_token_denom(1000).to_string()}));
+    }
+
+    alice.call(&mut runtime, &amm(), "new_pool", json!({"swap_fee": (to_token_denom(3) / 1000).to_string()}));
+    for token in &[sim_token_a(), sim_token_b()] {
+        alice.call(&mut runtime, &amm(), "bind_pool", json!({
+            "pool_id": "1",
+            "token_account_id": token,
+            "denorm": to_token_denom(10).to_string(),
+            "balance": to_token_denom(100).to_string()
+        }));
+    }
+    alice.call(&mut runtime, &amm(), "finalize_pool", json!({"pool_id": "1"}));
+
+    (runtime, alice, bob, carol)
+}
+
+#[test]
+fn simulate_bind_pulls_tokens_into_custody() {
+    let (runtime, _alice, _bob, _carol) = init_pool();
+
+    assert_eq!(token_balance(&runtime, &sim_token_a(), &amm()), to_token_denom(100));
+    assert_eq!(token_balance(&runtime, &sim_token_b(), &amm()), to_token_denom(100));
+    assert_eq!(token_balance(&runtime, &sim_token_a(), &sim_alice()), to_token_denom(900));
+
+    assert_eq!(pool_balance(&runtime, 1, &sim_token_a()), to_token_denom(100));
+    assert_eq!(pool_balance(&runtime, 1, &sim_token_b()), to_token_denom(100));
+}
+
+#[test]
+fn simulate_swap_moves_tokens() {
+    let (mut runtime, alice, bob, _carol) = init_pool();
+    let amount_in = to_token_denom(10);
+
+    alice.call(&mut runtime, &sim_token_a(), "transfer", json!({"new_owner_id": sim_bob(), "amount": amount_in.to_string()}));
+    bob.call(&mut runtime, &sim_token_a(), "inc_allowance", json!({"escrow_account_id": amm(), "amount": amount_in.to_string()}));
+
+    bob.call(&mut runtime, &amm(), "swap_exact_amount_in", json!({
+        "pool_id": "1",
+        "token_in": sim_token_a(),
+        "token_amount_in": amount_in.to_string(),
+        "token_out": sim_token_b(),
+        "min_amount_out": "0",
+        "max_price": to_token_denom(1000).to_string()
+    }));
+
+    let pool_balance_b = pool_balance(&runtime, 1, &sim_token_b());
+    assert_eq!(pool_balance(&runtime, 1, &sim_token_a()), to_token_denom(110));
+    assert_eq!(token_balance(&runtime, &sim_token_a(), &amm()), to_token_denom(110));
+    assert_eq!(token_balance(&runtime, &sim_token_a(), &sim_bob()), 0);
+
+    assert_eq!(token_balance(&runtime, &sim_token_b(), &amm()), pool_balance_b);
+    assert_eq!(token_balance(&runtime, &sim_token_b(), &sim_bob()), to_token_denom(100) - pool_balance_b);
+}
+
+#[test]
+fn simulate_exit_pays_out_tokens() {
+    let (mut runtime, alice, _bob, _carol) = init_pool();
+
+    alice.call(&mut runtime, &amm(), "exit_pool", json!({
+        "pool_id": "1",
+        "pool_amount_in": to_token_denom(50).to_string(),
+        "min_amounts_out": ["0", "0"]
+    }));
+
+    assert_eq!(token_balance(&runtime, &sim_token_a(), &amm()), to_token_denom(50));
+    assert_eq!(token_balance(&runtime, &sim_token_b(), &amm()), to_token_denom(50));
+    assert_eq!(token_balance(&runtime, &sim_token_a(), &sim_alice()), to_token_denom(950));
+    assert_eq!(pool_balance(&runtime, 1, &sim_token_a()), to_token_denom(50));
+}
+
+#[test]
+fn simulate_failed_pull_rolls_back_join() {
+    let (mut runtime, _alice, _bob, carol) = init_pool();
+
+    // Carol holds no tokens and gave no allowance, both pulls fail
+    carol.call(&mut runtime, &amm(), "join_pool", json!({
+        "pool_id": "1",
+        "pool_amount_out": to_token_denom(10).to_string(),
+        "max_amounts_in": [to_token_denom(10).to_string(), to_token_denom(10).to_string()]
+    }));
+
+    assert_eq!(pool_balance(&runtime, 1, &sim_token_a()), to_token_denom(100));
+    assert_eq!(pool_balance(&runtime, 1, &sim_token_b()), to_token_denom(100));
+    assert_eq!(view_u128(&runtime, &amm(), "get_pool_token_balance", json!({"pool_id": "1", "account_id": sim_carol()})), 0);
+    assert_eq!(view_u128(&runtime, &amm(), "get_pool_token_total_supply", json!({"pool_id": "1"})), to_token_denom(100));
+}
+
+#[test]
+fn simulate_failed_pull_rolls_back_swap() {
+    let (mut runtime, _alice, _bob, carol) = init_pool();
+
+    carol.call(&mut runtime, &amm(), "swap_exact_amount_in", json!({
+        "pool_id": "1",
+        "token_in": sim_token_a(),
+        "token_amount_in": to_token_denom(10).to_string(),
+        "token_out": sim_token_b(),
+        "min_amount_out": "0",
+        "max_price": to_token_denom(1000).to_string()
+    }));
+
+    assert_eq!(pool_balance(&runtime, 1, &sim_token_a()), to_token_denom(100));
+    assert_eq!(pool_balance(&runtime, 1, &sim_token_b()), to_token_denom(100));
+    assert_eq!(token_balance(&runtime, &sim_token_b(), &sim_carol()), 0);
+}
+
+#[test]
+fn simulate_gulp_absorbs_direct_transfers() {
+    let (mut runtime, alice, _bob, _carol) = init_pool();
+
+    alice.call(&mut runtime, &sim_token_a(), "transfer", json!({"new_owner_id": amm(), "amount": to_token_denom(5).to_string()}));
+    assert_eq!(pool_balance(&runtime, 1, &sim_token_a()), to_token_denom(100));
+
+    alice.call(&mut runtime, &amm(), "gulp", json!({"pool_id": "1", "token_account_id": sim_token_a()}));
+
+    assert_eq!(pool_balance(&runtime, 1, &sim_token_a()), to_token_denom(105));
+    assert_eq!(view_u128(&runtime, &amm(), "get_custody_balance", json!({"token_account_id": sim_token_a()})), to_token_denom(105));
+
+    // Nothing left to absorb
+    alice.call(&mut runtime, &amm(), "gulp", json!({"pool_id": "1", "token_account_id": sim_token_a()}));
+    assert_eq!(pool_balance(&runtime, 1, &sim_token_a()), to_token_denom(105));
+}
+
+#[test]
+fn simulate_protocol_fee_accrues_and_is_collected() {
+    let (mut runtime, alice, bob, _carol) = init_pool();
+    let amount_in = to_token_denom(10);
+
+    alice.call(&mut runtime, &amm(), "set_protocol_fee", json!({"protocol_fee": (to_token_denom(1) / 5).to_string()}));
+    alice.call(&mut runtime, &sim_token_a(), "transfer", json!({"new_owner_id": sim_bob(), "amount": amount_in.to_string()}));
+    bob.call(&mut runtime, &sim_token_a(), "inc_allowance", json!({"escrow_account_id": amm(), "amount": amount_in.to_string()}));
+
+    bob.call(&mut runtime, &amm(), "swap_exact_amount_in", json!({
+        "pool_id": "1",
+        "token_in": sim_token_a(),
+        "token_amount_in": amount_in.to_string(),
+        "token_out": sim_token_b(),
+        "min_amount_out": "0",
+        "max_price": to_token_denom(1000).to_string()
+    }));
+
+    let protocol_fee_amount = 6_000_000_000_000_000;
+    assert_eq!(pool_balance(&runtime, 1, &sim_token_a()), to_token_denom(110) - protocol_fee_amount);
+    assert_eq!(view_u128(&runtime, &amm(), "get_accrued_protocol_fees", json!({"token_account_id": sim_token_a()})), protocol_fee_amount);
+
+    let alice_balance = token_balance(&runtime, &sim_token_a(), &sim_alice());
+    alice.call(&mut runtime, &amm(), "collect_protocol_fees", json!({"token_account_ids": [sim_token_a()]}));
+
+    assert_eq!(token_balance(&runtime, &sim_token_a(), &sim_alice()), alice_balance + protocol_fee_amount);
+    assert_eq!(view_u128(&runtime, &amm(), "get_accrued_protocol_fees", json!({"token_account_id": sim_token_a()})), 0);
+    assert_eq!(token_balance(&runtime, &sim_token_a(), &amm()), pool_balance(&runtime, 1, &sim_token_a()));
+}
@@ -0,0 +1,113 @@
+use super::*;
+use crate::constants::{
+    MIN_FEE,
+    MAX_FEE,
+    SWAP_FEE_UPDATE_DELAY
+};
+use crate::pool::PendingSwapFee;
+
+fn create_pool(finalize: bool) -> (PoolFactory, U64) {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    let pool_id = contract.new_pool(swap_fee());
+
+    for token in &[token_a(), token_b()] {
+        bind_pool(&mut contract, 
+            pool_id,
+            token,
+            U128(to_token_denom(10)),
+            U128(to_token_denom(100))
+        );
+    }
+    if finalize {
+        contract.finalize_pool(pool_id);
+    }
+
+    (contract, pool_id)
+}
+
+fn new_swap_fee() -> U128 {
+    U128(to_token_denom(1) / 100)
+}
+
+#[test]
+fn test_set_swap_fee_before_finalize() {
+    let (mut contract, pool_id) = create_pool(false);
+    contract.set_swap_fee(pool_id, new_swap_fee());
+
+    assert_eq!(contract.pool_get_swap_fee(pool_id), new_swap_fee());
+    assert_eq!(contract.get_pool_pending_swap_fee(pool_id), None);
+}
+
+#[test]
+fn test_set_swap_fee_is_delayed_once_finalized() {
+    let (mut contract, pool_id) = create_pool(true);
+    contract.set_swap_fee(pool_id, new_swap_fee());
+
+    let pending = PendingSwapFee {
+        swap_fee: new_swap_fee(),
+        effective_time: U64(SWAP_FEE_UPDATE_DELAY)
+    };
+    assert_eq!(contract.pool_get_swap_fee(pool_id), swap_fee());
+    assert_eq!(contract.get_pool_pending_swap_fee(pool_id), Some(pending));
+
+    testing_env!(get_context(bob(), SWAP_FEE_UPDATE_DELAY - 1));
+    assert_eq!(contract.pool_get_swap_fee(pool_id), swap_fee());
+
+    testing_env!(get_context(bob(), SWAP_FEE_UPDATE_DELAY));
+    assert_eq!(contract.pool_get_swap_fee(pool_id), new_swap_fee());
+    assert_eq!(contract.get_pool_pending_swap_fee(pool_id), None);
+
+    // Swaps are priced with the fee that's effective, 1 / (1 - 1%) in an even pool
+    let preview = contract.get_pool_out_given_in(pool_id, &token_a(), U128(to_token_denom(1)), &token_b()).unwrap();
+    assert_eq!(preview.spot_price_before, U128(1010101010101010101));
+}
+
+#[test]
+fn test_new_swap_fee_restarts_delay() {
+    let (mut contract, pool_id) = create_pool(true);
+    contract.set_swap_fee(pool_id, U128(MAX_FEE));
+
+    testing_env!(get_context(alice(), SWAP_FEE_UPDATE_DELAY / 2));
+    contract.set_swap_fee(pool_id, new_swap_fee());
+
+    testing_env!(get_context(alice(), SWAP_FEE_UPDATE_DELAY));
+    assert_eq!(contract.pool_get_swap_fee(pool_id), swap_fee());
+    assert_eq!(
+        contract.get_pool_pending_swap_fee(pool_id).map(|pending| pending.effective_time),
+        Some(U64(SWAP_FEE_UPDATE_DELAY / 2 + SWAP_FEE_UPDATE_DELAY))
+    );
+}
+
+#[test]
+#[should_panic(expected = "ERR_NO_CONTROLLER")]
+fn test_set_swap_fee_not_controller() {
+    let (mut contract, pool_id) = create_pool(true);
+    testing_env!(get_context(bob(), 0));
+    contract.set_swap_fee(pool_id, new_swap_fee());
+}
+
+#[test]
+#[should_panic(expected = "ERR_MIN_FEE")]
+fn test_set_swap_fee_below_min() {
+    let (mut contract, pool_id) = create_pool(true);
+    contract.set_swap_fee(pool_id, U128(MIN_FEE - 1));
+}
+
+#[test]
+#[should_panic(expected = "ERR_MAX_FEE")]
+fn test_set_swap_fee_above_max() {
+    let (mut contract, pool_id) = create_pool(true);
+    contract.set_swap_fee(pool_id, U128(MAX_FEE + 1));
+}
+
+#[test]
+#[should_panic(expected = "ERR_MIN_FEE")]
+fn test_new_pool_below_min_fee() {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+    contract.new_pool(U128(0));
+}
@@ -1,4 +1,5 @@
 use super::*;
+use crate::constants::MIN_FEE;
 
 fn create_even_pool() -> (PoolFactory, U64) {
     let context = get_context(alice(), 0);
@@ -8,7 +9,7 @@ fn create_even_pool() -> (PoolFactory, U64) {
     let pool_id = contract.new_pool(swap_fee());
 
     // Token a is stable coin worth $1
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_a(),
         U128(to_token_denom(10)),
@@ -16,7 +17,7 @@ fn create_even_pool() -> (PoolFactory, U64) {
     );
 
     // Token b is governance token worth $200
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_b(),
         U128(to_token_denom(10)),
@@ -49,15 +50,72 @@ fn test_swap_exact_amt_in_even_pool() {
     assert_eq!(expected_spot_price_after, u128::from(spot_price_after));
 }
 
+#[test]
+fn test_swap_exact_amt_out_even_pool() {
+    let  (mut contract, pool_id) = create_even_pool();
+    let tokens_out = to_token_denom(1) / 10;
+
+    let (tokens_in, spot_price_after) = contract.swap_exact_amount_out(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(12)),
+        &token_b(),
+        U128(tokens_out),
+        U128(to_token_denom(1000))
+    );
+
+    let expected_tokens_in = 11144544745347152558;
+    let expected_spot_price_after = 123865535211576008624;
+
+    assert_eq!(U128(expected_tokens_in), tokens_in);
+    assert_eq!(expected_spot_price_after, u128::from(spot_price_after));
+
+    let pool_a_balance: u128 = contract.get_pool_balance(pool_id, &token_a()).into();
+    let pool_b_balance: u128 = contract.get_pool_balance(pool_id, &token_b()).into();
+
+    assert_eq!(pool_a_balance, to_token_denom(100) + expected_tokens_in);
+    assert_eq!(pool_b_balance, to_token_denom(1) - tokens_out);
+}
+
+#[test]
+#[should_panic(expected = "ERR_LIMIT_IN")]
+fn test_swap_exact_amt_out_limit_in() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    contract.swap_exact_amount_out(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(11)),
+        &token_b(),
+        U128(to_token_denom(1) / 10),
+        U128(to_token_denom(1000))
+    );
+}
+
+#[test]
+#[should_panic(expected = "ERR_MAX_OUT_RATIO")]
+fn test_swap_exact_amt_out_max_out_ratio() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    contract.swap_exact_amount_out(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(1000)),
+        &token_b(),
+        U128(to_token_denom(1) / 2),
+        U128(to_token_denom(1000))
+    );
+}
+
 fn create_uneven_pool() -> (PoolFactory, U64) {
     let context = get_context(alice(), 0);
     testing_env!(context);
     let mut contract = PoolFactory::init(alice());
     
-    let pool_id = contract.new_pool(U128(0));
+    let pool_id = contract.new_pool(U128(MIN_FEE));
 
     // Token a is stable coin worth $1
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_a(),
         U128(to_token_denom(20)),
@@ -65,7 +123,7 @@ fn create_uneven_pool() -> (PoolFactory, U64) {
     );
 
     // Token b is a token worth $0.5
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_b(),
         U128(to_token_denom(10)),
@@ -73,7 +131,7 @@ fn create_uneven_pool() -> (PoolFactory, U64) {
     );
     
     // Token c is a token worth 0.5
-    contract.bind_pool(
+    bind_pool(&mut contract, 
         pool_id,
         &token_c(),
         U128(to_token_denom(10)),
@@ -90,8 +148,8 @@ fn test_swap_exact_amt_in_uneven_pool() {
     let tokens_in = to_token_denom(10);
 
     /*** verify base state pricing ***/
-    let expected_a_to_b_spot_price = to_token_denom(5) / 10;
-    let expected_a_to_c_spot_price = to_token_denom(5) / 10;
+    let expected_a_to_b_spot_price = to_token_denom(2);
+    let expected_a_to_c_spot_price = to_token_denom(2);
 
     let a_to_b_spot_price: u128 = contract.get_pool_spot_price_sans_fee(pool_id, &token_a(), &token_b()).into();
     let a_to_c_spot_price: u128 = contract.get_pool_spot_price_sans_fee(pool_id, &token_a(), &token_c()).into();
@@ -109,10 +167,14 @@ fn test_swap_exact_amt_in_uneven_pool() {
         U128(to_token_denom(1000))
     );
 
+    // Priced with the pool's MIN_FEE swap fee
+    assert_eq!(tokens_out, U128(4648521757909204550));
+    assert_eq!(spot_price_after, U128(2315252094752100005));
+
 
     /*** verify post swap pricing ***/
-    let expected_a_to_b_spot_price = 805255000000000000;
-    let expected_a_to_c_spot_price = to_token_denom(55) / 100;
+    let expected_a_to_b_spot_price = 2315249779500005253;
+    let expected_a_to_c_spot_price = to_token_denom(21) / 10;
 
     let a_to_b_spot_price: u128 = contract.get_pool_spot_price_sans_fee(pool_id, &token_a(), &token_b()).into();
     let a_to_c_spot_price: u128 = contract.get_pool_spot_price_sans_fee(pool_id, &token_a(), &token_c()).into();
@@ -0,0 +1,195 @@
+use super::*;
+use crate::token::VaultId;
+use crate::constants::{
+    INIT_POOL_SUPPLY
+};
+
+fn create_even_pool() -> (PoolFactory, U64) {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+    
+    let pool_id = contract.new_pool(swap_fee());
+
+    bind_pool(&mut contract, 
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(100))
+    );
+
+    bind_pool(&mut contract, 
+        pool_id,
+        &token_b(),
+        U128(to_token_denom(10)),
+        U128(to_token_denom(1))
+    );
+    contract.finalize_pool(pool_id);
+
+    (contract, pool_id)
+}
+
+#[test]
+fn test_vault_swap() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    // The token contract forwards the vault on behalf of bob
+    testing_env!(get_context(token_a(), 0));
+
+    let payload = format!(
+        r#"{{"action": "swap", "pool_id": "{}", "token_out": "{}", "max_price": "{}"}}"#, 
+        u64::from(pool_id), 
+        token_b(), 
+        to_token_denom(1000)
+    );
+    contract.on_receive_with_vault(bob(), U128(to_token_denom(25)), VaultId(0), payload);
+
+    let pool_a_balance: u128 = contract.get_pool_balance(pool_id, &token_a()).into();
+    let pool_b_balance: u128 = contract.get_pool_balance(pool_id, &token_b()).into();
+
+    // Same trade as `test_swap_exact_amt_in_even_pool`
+    assert_eq!(pool_a_balance, to_token_denom(125));
+    assert_eq!(pool_b_balance, to_token_denom(1) - 199519711827096258);
+}
+
+#[test]
+fn test_vault_swap_exact_out() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    testing_env!(get_context(token_a(), 0));
+
+    let payload = format!(
+        r#"{{"action": "swap", "pool_id": "{}", "token_out": "{}", "token_amount_out": "{}", "max_price": "{}"}}"#, 
+        u64::from(pool_id), 
+        token_b(), 
+        to_token_denom(1) / 10,
+        to_token_denom(1000)
+    );
+    contract.on_receive_with_vault(bob(), U128(to_token_denom(20)), VaultId(0), payload);
+
+    // Only the amount in that was needed is taken from the vault, the rest is refunded
+    let pool_a_balance: u128 = contract.get_pool_balance(pool_id, &token_a()).into();
+    assert_eq!(pool_a_balance, to_token_denom(100) + 11144544745347152558);
+}
+
+#[test]
+fn test_vault_join() {
+    let  (mut contract, pool_id) = create_even_pool();
+
+    testing_env!(get_context(token_a(), 0));
+
+    let payload = format!(r#"{{"action": "join", "pool_id": "{}"}}"#, u64::from(pool_id));
+    contract.on_receive_with_vault(bob(), U128(to_token_denom(10)), VaultId(0), payload);
+
+    // Pool tokens are only minted once the vault withdrawal resolved
+    assert_eq!(contract.get_pool_token_balance(pool_id, &bob()), U128(0));
+    let expected_pool_amount_out = 4873733603880249500;
+    resolve_join(&mut contract, pool_id, bob(), vec![(token_a(), to_token_denom(10))], expected_pool_amount_out);
+
+    let joined_pool_tokens: u128 = contract.get_pool_token_balance(pool_id, &bob()).into();
+    let pool_a_balance: u128 = contract.get_pool_balance(pool_id, &token_a()).into();
+
+    assert_eq!(joined_pool_tokens, expected_pool_amount_out);
+    assert_eq!(pool_a_balance, to_token_denom(110));
+}
+
+// Alice adds `amount` of `token` through a vault, the withdrawal from the vault succeeds unless `success` is false
+fn add_liquidity(contract: &mut PoolFactory, pool_id: U64, token: AccountId, amount: u128, vault_id: VaultId, success: bool) {
+    testing_env!(get_context(token.to_string(), 0));
+    let payload = format!(r#"{{"action": "add_liquidity", "pool_id": "{}", "denorm": "{}"}}"#, u64::from(pool_id), to_token_denom(10));
+    contract.on_receive_with_vault(alice(), U128(amount), vault_id, payload);
+
+    testing_env_with_promise_results(get_context(alice(), 0), promise_results(&[success]));
+    contract.resolve_transfer_in(pool_id, token, U128(amount));
+    testing_env!(get_context(alice(), 0));
+}
+
+#[test]
+fn test_vault_add_liquidity() {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+    let pool_id = contract.new_pool(swap_fee());
+    bind_pool(&mut contract, pool_id, &token_a(), U128(to_token_denom(10)), U128(to_token_denom(50)));
+    bind_pool(&mut contract, pool_id, &token_b(), U128(to_token_denom(10)), U128(to_token_denom(50)));
+
+    add_liquidity(&mut contract, pool_id, token_a(), to_token_denom(25), VaultId(0), true);
+    add_liquidity(&mut contract, pool_id, token_a(), to_token_denom(25), VaultId(1), true);
+    add_liquidity(&mut contract, pool_id, token_b(), to_token_denom(50), VaultId(0), true);
+
+    contract.finalize_pool(pool_id);
+
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(100)));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_b()), U128(to_token_denom(100)));
+    assert_eq!(contract.get_pool_token_balance(pool_id, &alice()), U128(INIT_POOL_SUPPLY));
+}
+
+#[test]
+fn test_failed_vault_add_liquidity_is_rolled_back() {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+    let pool_id = contract.new_pool(swap_fee());
+    bind_pool(&mut contract, pool_id, &token_a(), U128(to_token_denom(10)), U128(to_token_denom(50)));
+
+    add_liquidity(&mut contract, pool_id, token_a(), to_token_denom(25), VaultId(0), false);
+
+    assert!(!contract.pool_is_locked(pool_id));
+    assert_eq!(contract.get_pool_balance(pool_id, &token_a()), U128(to_token_denom(50)));
+    assert_eq!(contract.get_custody_balance(&token_a()), U128(to_token_denom(50)));
+}
+
+#[test]
+#[should_panic(expected = "ERR_REENTRY")]
+fn test_finalize_while_vault_deposit_pending() {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+    let pool_id = contract.new_pool(swap_fee());
+    bind_pool(&mut contract, pool_id, &token_a(), U128(to_token_denom(10)), U128(to_token_denom(50)));
+    bind_pool(&mut contract, pool_id, &token_b(), U128(to_token_denom(10)), U128(to_token_denom(50)));
+
+    testing_env!(get_context(token_a(), 0));
+    let payload = format!(r#"{{"action": "add_liquidity", "pool_id": "{}", "denorm": "{}"}}"#, u64::from(pool_id), to_token_denom(10));
+    contract.on_receive_with_vault(alice(), U128(to_token_denom(50)), VaultId(0), payload);
+
+    testing_env!(get_context(alice(), 0));
+    contract.finalize_pool(pool_id);
+}
+
+#[test]
+#[should_panic(expected = "ERR_NO_CONTROLLER")]
+fn test_vault_add_liquidity_not_controller() {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+    let pool_id = contract.new_pool(swap_fee());
+    bind_pool(&mut contract, pool_id, &token_a(), U128(to_token_denom(10)), U128(to_token_denom(50)));
+
+    testing_env!(get_context(token_a(), 0));
+    let payload = format!(r#"{{"action": "add_liquidity", "pool_id": "{}", "denorm": "{}"}}"#, u64::from(pool_id), to_token_denom(10));
+    contract.on_receive_with_vault(bob(), U128(to_token_denom(50)), VaultId(0), payload);
+}
+
+#[test]
+#[should_panic(expected = "ERR_NOT_BOUND")]
+fn test_vault_add_liquidity_unbound_token() {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+    let pool_id = contract.new_pool(swap_fee());
+
+    // Any contract can claim to be a token sending on behalf of the controller
+    testing_env!(get_context(carol(), 0));
+    let payload = format!(r#"{{"action": "add_liquidity", "pool_id": "{}", "denorm": "{}"}}"#, u64::from(pool_id), to_token_denom(10));
+    contract.on_receive_with_vault(alice(), U128(to_token_denom(50)), VaultId(0), payload);
+}
+
+#[test]
+#[should_panic(expected = "ERR_INVALID_PAYLOAD")]
+fn test_vault_invalid_payload() {
+    let  (mut contract, _pool_id) = create_even_pool();
+
+    testing_env!(get_context(token_a(), 0));
+    contract.on_receive_with_vault(bob(), U128(to_token_denom(10)), VaultId(0), "{}".to_string());
+}
@@ -0,0 +1,205 @@
+use super::*;
+
+fn create_pool(denorm_a: u128, denorm_b: u128) -> (PoolFactory, U64) {
+    let context = get_context(alice(), 0);
+    testing_env!(context);
+    let mut contract = PoolFactory::init(alice());
+
+    let pool_id = contract.new_pool(swap_fee());
+
+    bind_pool(&mut contract, 
+        pool_id,
+        &token_a(),
+        U128(denorm_a),
+        U128(to_token_denom(100))
+    );
+
+    bind_pool(&mut contract, 
+        pool_id,
+        &token_b(),
+        U128(denorm_b),
+        U128(to_token_denom(100))
+    );
+    contract.finalize_pool(pool_id);
+
+    (contract, pool_id)
+}
+
+const HOUR: u64 = 3_600_000_000_000;
+
+// Moves the weights of token a and b from 40/10 to 10/40 between hour 1 and 2
+fn create_bootstrapping_pool() -> (PoolFactory, U64) {
+    let (mut contract, pool_id) = create_pool(to_token_denom(40), to_token_denom(10));
+
+    contract.update_weights_gradually(
+        pool_id,
+        vec![token_a(), token_b()],
+        vec![U128(to_token_denom(10)), U128(to_token_denom(40))],
+        U64(HOUR),
+        U64(2 * HOUR)
+    );
+
+    (contract, pool_id)
+}
+
+fn swap_a_for_b(contract: &mut PoolFactory, pool_id: U64, block_timestamp: u64) -> u128 {
+    testing_env!(get_context(bob(), block_timestamp));
+    let (amount_out, _) = contract.swap_exact_amount_in(
+        pool_id,
+        &token_a(),
+        U128(to_token_denom(10)),
+        &token_b(),
+        U128(0),
+        U128(to_token_denom(1000))
+    );
+    amount_out.into()
+}
+
+#[test]
+fn test_weights_are_interpolated() {
+    let (contract, pool_id) = create_bootstrapping_pool();
+
+    testing_env!(get_context(alice(), HOUR / 2));
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &token_a()), U128(to_token_denom(40)));
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &token_b()), U128(to_token_denom(10)));
+    assert_eq!(contract.get_pool_total_denormalized_weight(pool_id), U128(to_token_denom(50)));
+
+    testing_env!(get_context(alice(), 3 * HOUR / 2));
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &token_a()), U128(to_token_denom(25)));
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &token_b()), U128(to_token_denom(25)));
+    assert_eq!(contract.get_pool_spot_price_sans_fee(pool_id, &token_a(), &token_b()), U128(to_token_denom(1)));
+
+    testing_env!(get_context(alice(), 3 * HOUR));
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &token_a()), U128(to_token_denom(10)));
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &token_b()), U128(to_token_denom(40)));
+    assert_eq!(contract.get_pool_spot_price_sans_fee(pool_id, &token_a(), &token_b()), U128(to_token_denom(4)));
+}
+
+#[test]
+fn test_swap_uses_interpolated_weights() {
+    let (mut contract, pool_id) = create_bootstrapping_pool();
+    let amount_out = swap_a_for_b(&mut contract, pool_id, 3 * HOUR / 2);
+
+    // Halfway through the update the pool prices like a 25/25 pool
+    let (mut even_contract, even_pool_id) = create_pool(to_token_denom(25), to_token_denom(25));
+    let even_amount_out = swap_a_for_b(&mut even_contract, even_pool_id, 3 * HOUR / 2);
+
+    assert_eq!(amount_out, even_amount_out);
+}
+
+#[test]
+fn test_swap_after_update_uses_end_weights() {
+    let (mut contract, pool_id) = create_bootstrapping_pool();
+    let amount_out = swap_a_for_b(&mut contract, pool_id, 5 * HOUR / 2);
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &token_b()), U128(to_token_denom(40)));
+
+    let (mut end_contract, end_pool_id) = create_pool(to_token_denom(10), to_token_denom(40));
+    let end_amount_out = swap_a_for_b(&mut end_contract, end_pool_id, 5 * HOUR / 2);
+
+    assert_eq!(amount_out, end_amount_out);
+}
+
+#[test]
+#[should_panic(expected = "ERR_NO_CONTROLLER")]
+fn test_update_weights_not_controller() {
+    let (mut contract, pool_id) = create_pool(to_token_denom(10), to_token_denom(10));
+    testing_env!(get_context(bob(), 0));
+    contract.update_weights_gradually(
+        pool_id,
+        vec![token_a()],
+        vec![U128(to_token_denom(20))],
+        U64(HOUR),
+        U64(2 * HOUR)
+    );
+}
+
+#[test]
+#[should_panic(expected = "ERR_MAX_TOTAL_WEIGHT")]
+fn test_update_weights_max_total_weight() {
+    let (mut contract, pool_id) = create_pool(to_token_denom(10), to_token_denom(10));
+    contract.update_weights_gradually(
+        pool_id,
+        vec![token_a()],
+        vec![U128(to_token_denom(45))],
+        U64(HOUR),
+        U64(2 * HOUR)
+    );
+}
+
+#[test]
+#[should_panic(expected = "ERR_MIN_WEIGHT")]
+fn test_update_weights_min_weight() {
+    let (mut contract, pool_id) = create_pool(to_token_denom(10), to_token_denom(10));
+    contract.update_weights_gradually(
+        pool_id,
+        vec![token_a()],
+        vec![U128(to_token_denom(1) / 2)],
+        U64(HOUR),
+        U64(2 * HOUR)
+    );
+}
+
+#[test]
+#[should_panic(expected = "ERR_INVALID_SCHEDULE")]
+fn test_update_weights_start_in_the_past() {
+    let (mut contract, pool_id) = create_pool(to_token_denom(10), to_token_denom(10));
+    testing_env!(get_context(alice(), 2 * HOUR));
+    contract.update_weights_gradually(
+        pool_id,
+        vec![token_a()],
+        vec![U128(to_token_denom(20))],
+        U64(HOUR),
+        U64(3 * HOUR)
+    );
+}
+
+#[test]
+#[should_panic(expected = "ERR_MIN_DURATION")]
+fn test_update_weights_min_duration() {
+    let (mut contract, pool_id) = create_pool(to_token_denom(10), to_token_denom(10));
+    contract.update_weights_gradually(
+        pool_id,
+        vec![token_a()],
+        vec![U128(to_token_denom(20))],
+        U64(HOUR),
+        U64(2 * HOUR - 1)
+    );
+}
+
+#[test]
+#[should_panic(expected = "ERR_MIN_DURATION")]
+fn test_update_weights_end_before_start() {
+    let (mut contract, pool_id) = create_pool(to_token_denom(10), to_token_denom(10));
+    contract.update_weights_gradually(
+        pool_id,
+        vec![token_a()],
+        vec![U128(to_token_denom(20))],
+        U64(2 * HOUR),
+        U64(HOUR)
+    );
+}
+
+#[test]
+fn test_update_weights_starts_from_current_weights() {
+    let (mut contract, pool_id) = create_bootstrapping_pool();
+
+    // Halfway through, the new update takes over from 25/25 instead of jumping the weights
+    testing_env!(get_context(alice(), 3 * HOUR / 2));
+    contract.update_weights_gradually(
+        pool_id,
+        vec![token_a()],
+        vec![U128(to_token_denom(5))],
+        U64(2 * HOUR),
+        U64(3 * HOUR)
+    );
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &token_a()), U128(to_token_denom(25)));
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &token_b()), U128(to_token_denom(25)));
+
+    testing_env!(get_context(alice(), 2 * HOUR));
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &token_a()), U128(to_token_denom(25)));
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &token_b()), U128(to_token_denom(25)));
+
+    testing_env!(get_context(alice(), 3 * HOUR));
+    assert_eq!(contract.get_pool_denormalized_weight(pool_id, &token_a()), U128(to_token_denom(5)));
+    assert_eq!(contract.get_pool_total_denormalized_weight(pool_id), U128(to_token_denom(30)));
+}
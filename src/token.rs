@@ -42,6 +42,16 @@ impl VaultId {
     }
 }
 
+/// Metadata of a pool token, mirrors NEP-148 `ft_metadata`
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct FungibleTokenMetadata {
+    pub spec: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
 #[derive(BorshDeserialize, BorshSerialize)]
 pub struct Vault {
     pub sender_id: AccountId,
@@ -86,7 +96,8 @@ impl Token {
         self.accounts.insert(account_id, &new_balance);
     }
 
-    pub fn faux_burn(&mut self, amount: u128) {
+    pub fn burn(&mut self, account_id: &AccountId, amount: u128) {
+        self.withdraw(account_id, amount);
         self.total_supply -= amount;
     }
 
@@ -141,12 +152,19 @@ impl FungibleTokenVault {
         self.token.mint(amount, account_id);
     }
 
-    pub fn faux_burn(&mut self, amount: u128) {
-        self.token.faux_burn(amount);
+    pub fn burn(&mut self, account_id: &AccountId, amount: u128) {
+        self.token.burn(account_id, amount);
     }
 
     pub fn transfer_unsafe(&mut self, receiver_id: &AccountId, amount: u128) {
-        self.token.withdraw(&env::predecessor_account_id(), amount);
+        self.transfer(&env::predecessor_account_id(), receiver_id, amount);
+    }
+
+    pub fn transfer(&mut self, sender_id: &AccountId, receiver_id: &AccountId, amount: u128) {
+        assert_ne!(sender_id, receiver_id, "ERR_SELF_TRANSFER");
+        assert!(env::is_valid_account_id(receiver_id.as_bytes()), "ERR_INVALID_ACCOUNT_ID");
+
+        self.token.withdraw(sender_id, amount);
         self.token.deposit(receiver_id, amount);
     }
 